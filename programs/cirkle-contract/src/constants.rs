@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+/// Prices are normalised to USD with this many decimals (micro-dollars).
+pub const PRICE_DECIMALS: u32 = 6;
+
+/// City tokens are minted with 6 decimals.
+pub const TOKEN_DECIMALS: u32 = 6;
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Default maximum age of a price before it is rejected as stale.
+pub const DEFAULT_MAX_PRICE_AGE: u64 = 60;
+
+/// Default maximum age of an admin-set city price. Property valuations change
+/// slowly, so they need not be pushed as often as market prices.
+pub const DEFAULT_MAX_CITY_PRICE_AGE: u64 = 7 * SECONDS_PER_DAY as u64;
+
+/// Default maximum confidence interval, in basis points of the price.
+pub const DEFAULT_MAX_CONFIDENCE_BPS: u16 = 200;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Feature switches stored in `ProtocolConfig.features`.
+pub const FEATURE_TRADING: u8 = 1 << 0;
+pub const FEATURE_STAKING: u8 = 1 << 1;
+pub const FEATURE_REWARDS: u8 = 1 << 2;
+pub const FEATURES_ALL: u8 = FEATURE_TRADING | FEATURE_STAKING | FEATURE_REWARDS;
+
+/// Fees are capped at 10%.
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// By default the vault must stay fully backed.
+pub const DEFAULT_MIN_RESERVE_RATIO_BPS: u16 = 10_000;
+
+/// The admin may lower the reserve ratio to half backed, but no further.
+pub const MIN_RESERVE_RATIO_FLOOR_BPS: u16 = 5_000;
+
+/// Reserve ratios above 2x would lock the vault for no benefit.
+pub const MIN_RESERVE_RATIO_CEILING_BPS: u16 = 20_000;
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+/// Fixed staking reward rate for new cities. Stakers earn from distributed
+/// rental yield unless the admin sets a rate.
+pub const DEFAULT_REWARD_APR_BPS: u16 = 0;
+
+/// Reward rates are capped at 50% a year.
+pub const MAX_REWARD_APR_BPS: u16 = 5_000;
+
+/// Minimum time between two reward rate changes for a city.
+pub const REWARD_RATE_CHANGE_COOLDOWN: i64 = SECONDS_PER_DAY;
+
+/// Cooldown before unstaked tokens can be withdrawn, for new cities.
+pub const DEFAULT_UNBONDING_PERIOD: i64 = 7 * SECONDS_PER_DAY;
+
+pub const MAX_UNBONDING_PERIOD: i64 = 30 * SECONDS_PER_DAY;
+
+/// Early-exit penalties are capped at 50% of the unstaked amount.
+pub const MAX_EARLY_EXIT_PENALTY_BPS: u16 = 5_000;
+
+/// Scale of `StakePool.acc_reward_per_token`.
+pub const ACC_PRECISION: u128 = 1_000_000_000_000;
+
+/// Program whose transfer hook new city mints call. It lives outside this
+/// program because Token-2022 calling back into the program that started a
+/// transfer would be rejected as reentrancy.
+pub const TRANSFER_HOOK_PROGRAM_ID: Pubkey =
+    pubkey!("9tDGgBveTVfNJTQjHqftwhyc5Z4Qw87KUKC94d4taLiy");
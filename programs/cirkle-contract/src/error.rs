@@ -26,4 +26,102 @@ pub enum RwaError {
     NoRewardsAvailable,
     #[msg("Invalid Price")]
     InvalidPrice,
+    #[msg("Invalid Price Feed")]
+    InvalidPriceFeed,
+    #[msg("Price Is Stale")]
+    StalePrice,
+    #[msg("Price Confidence Too Low")]
+    PriceConfidenceTooLow,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid City Name")]
+    InvalidCityName,
+    #[msg("Invalid Metadata URI")]
+    InvalidMetadataUri,
+    #[msg("Feature Disabled")]
+    FeatureDisabled,
+    #[msg("Invalid Vault")]
+    InvalidVault,
+    #[msg("Fee Too High")]
+    FeeTooHigh,
+    #[msg("Trading Is Paused")]
+    TradingPaused,
+    #[msg("Staking Is Paused")]
+    StakingPaused,
+    #[msg("Reward Payouts Are Paused")]
+    RewardsPaused,
+    #[msg("Buying Is Not Allowed For This City")]
+    CityBuyDisabled,
+    #[msg("Selling Is Not Allowed For This City")]
+    CitySellDisabled,
+    #[msg("Staking Is Not Allowed For This City")]
+    CityStakeDisabled,
+    #[msg("Invalid City Status Transition")]
+    InvalidStatusTransition,
+    #[msg("Slippage Exceeded")]
+    SlippageExceeded,
+    #[msg("Deadline Exceeded")]
+    DeadlineExceeded,
+    #[msg("Vault Reserve Ratio Too Low")]
+    ReserveRatioTooLow,
+    #[msg("Reward Rate Too High")]
+    RewardRateTooHigh,
+    #[msg("Reward Rate Changed Too Recently")]
+    RewardRateChangeTooSoon,
+    #[msg("Stake Is Still Locked")]
+    StakeLocked,
+    #[msg("Lock Tier Cannot Be Lowered")]
+    LockTierDowngrade,
+    #[msg("Early Exit Penalty Too High")]
+    EarlyExitPenaltyTooHigh,
+    #[msg("Positions Must Differ")]
+    SamePosition,
+    #[msg("Invalid Reward Mint")]
+    InvalidRewardMint,
+    #[msg("Reward Pool Is Not Empty")]
+    RewardPoolNotEmpty,
+    #[msg("Stake Position Is Not Empty")]
+    PositionNotEmpty,
+    #[msg("Rewards Must Be Claimed First")]
+    RewardsUnclaimed,
+    #[msg("Nothing Is Unbonding")]
+    NothingUnbonding,
+    #[msg("Unbonding Period Not Finished")]
+    UnbondingNotFinished,
+    #[msg("Unbonding In Progress")]
+    UnbondingInProgress,
+    #[msg("Invalid Unbonding Period")]
+    InvalidUnbondingPeriod,
+    #[msg("Checkpoint No Longer Available")]
+    CheckpointUnavailable,
+    #[msg("KYC Record Required")]
+    KycRequired,
+    #[msg("KYC Not Verified")]
+    KycNotVerified,
+    #[msg("KYC Expired")]
+    KycExpired,
+    #[msg("Invalid Jurisdiction Code")]
+    InvalidJurisdiction,
+    #[msg("Invalid KYC Expiry")]
+    InvalidKycExpiry,
+    #[msg("Jurisdiction Blocked For This City")]
+    JurisdictionBlocked,
+    #[msg("Too Many Blocked Jurisdictions")]
+    TooManyJurisdictions,
+    #[msg("Invalid Metadata Value")]
+    InvalidMetadataValue,
+    #[msg("Rewards Are Still Owed To Stakers")]
+    RewardsOutstanding,
+    #[msg("Insufficient Penalty Balance")]
+    InsufficientPenaltyBalance,
+    #[msg("Reward Price Not Set")]
+    RewardPriceNotSet,
+    #[msg("Invalid Pause Flags")]
+    InvalidPauseFlags,
+    #[msg("Invalid Guardian")]
+    InvalidGuardian,
+    #[msg("Reward Rate Requires City Token Rewards")]
+    RewardRateRequiresCityMint,
+    #[msg("Reserve Ratio Out Of Range")]
+    ReserveRatioOutOfRange,
 }
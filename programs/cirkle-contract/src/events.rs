@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct TokensBought {
+    pub user: Pubkey,
+    pub city_mint: Pubkey,
+    pub lamports_paid: u64,
+    pub fee_lamports: u64,
+    pub tokens_minted: u64,
+    pub sol_price_usd: u64,
+    pub city_price_usd: u64,
+}
+
+#[event]
+pub struct TokensSold {
+    pub user: Pubkey,
+    pub city_mint: Pubkey,
+    pub tokens_burned: u64,
+    pub lamports_returned: u64,
+    pub fee_lamports: u64,
+    pub sol_price_usd: u64,
+    pub city_price_usd: u64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct HolderFreezeChanged {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub city_mint: Pubkey,
+    pub sequence: u64,
+    pub frozen: bool,
+    pub reason_code: u16,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct CityMetadataUpdated {
+    pub city_mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
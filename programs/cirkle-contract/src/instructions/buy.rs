@@ -1,12 +1,20 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    metadata::{create_metadata_accounts_v3, CreateMetadataAccountsV3},
-    token::{self, Mint, MintTo, Token, TokenAccount},
+    associated_token::{
+        self, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_interface::{mint_to, MintTo, TokenInterface},
 };
 
+use crate::constants::BPS_DENOMINATOR;
+use crate::constants::FEATURE_TRADING;
 use crate::error::RwaError;
-use crate::state::{CityConfig, Vault};
+use crate::events::TokensBought;
+use crate::state::{CityConfig, CityPrice, FeeTreasury, JurisdictionRules, ProtocolConfig, Vault};
+use crate::utils::{
+    check_deadline, deposit_to_vault, fee_amount, lamports_to_tokens, load_account, require_kyc,
+    save_account, tokens_to_lamports_rounded_up, OraclePrice,
+};
 
 #[derive(Accounts)]
 #[instruction(city_name: String)]
@@ -14,58 +22,86 @@ pub struct Buy<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    /// CHECK: Admin that owns the vault PDA
+    /// CHECK: Caller's KYC record, loaded so a missing one fails with `KycRequired`
+    #[account(
+        seeds = [b"kyc", user.key().as_ref()],
+        bump,
+    )]
+    pub kyc_record: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = vault @ RwaError::InvalidVault,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
     #[account(mut)]
-    pub admin: UncheckedAccount<'info>,
+    pub vault: Account<'info, Vault>,
 
     #[account(
         mut,
-        seeds = [b"protocol_admin", admin.key().as_ref()],
-        bump
+        seeds = [b"fee-treasury"],
+        bump = fee_treasury.bump,
     )]
-    pub vault: Account<'info, Vault>,
+    pub fee_treasury: Account<'info, FeeTreasury>,
+
+    /// CHECK: Pyth SOL/USD price account, parsed in `buy_token`
+    #[account(address = protocol_config.sol_usd_feed @ RwaError::InvalidPriceFeed)]
+    pub sol_usd_price: UncheckedAccount<'info>,
 
-    /// City configuration - stores metadata about the city's token
+    /// CHECK: Admin-maintained USD price of one city token, loaded in `buy_token`
     #[account(
-        init_if_needed,
-        payer = user,
+        seeds = [b"city-price", city_name.as_bytes()],
+        bump,
+    )]
+    pub city_price: UncheckedAccount<'info>,
+
+    /// CHECK: City configuration written by `create_city`. Loaded in `buy_token`
+    /// so that unregistered cities fail with `CityNotFound`.
+    #[account(
+        mut,
         seeds = [b"city-config", city_name.as_bytes()],
         bump,
-        space = 8 + CityConfig::INIT_SPACE,
     )]
-    pub city_config: Account<'info, CityConfig>,
+    pub city_config: UncheckedAccount<'info>,
 
-    /// City-specific mint - unique for each city
-    /// This gets created on the FIRST buy for this city
+    /// CHECK: City-specific mint created by `create_city`, matched against `city_config`
     #[account(
-        init_if_needed,
-        payer = user,
+        mut,
         seeds = [b"city-mint", city_name.as_bytes()],
         bump,
-        mint::decimals = 6,
-        mint::authority = vault,
-        mint::freeze_authority = vault
     )]
-    pub city_mint: Account<'info, Mint>,
+    pub city_mint: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"jurisdiction-rules", city_mint.key().as_ref()],
+        bump = jurisdiction_rules.bump,
+    )]
+    pub jurisdiction_rules: Account<'info, JurisdictionRules>,
 
-    /// User's Associated Token Account for this specific city token
+    /// CHECK: User's Associated Token Account for this city token, created if missing
     #[account(
-        init_if_needed,
-        payer = user,
-        associated_token::mint = city_mint,
-        associated_token::authority = user
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &user.key(),
+            &city_mint.key(),
+            &token_program.key(),
+        ),
     )]
-    pub user_ata: Account<'info, TokenAccount>,
+    pub user_ata: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+}
 
-    #[account(mut)]
-    /// CHECK: Metaplex will verify this is the correct metadata PDA
-    pub metadata: UncheckedAccount<'info>,
-    pub token_metadata_program: Program<'info, anchor_spl::metadata::Metadata>,
+/// Validated city and prices shared by exact-input and exact-output buys.
+struct BuyQuote {
+    city_config: CityConfig,
+    sol_price_usd: u64,
+    city_price_usd: u64,
+    fee_bps: u16,
 }
 
 impl<'info> Buy<'info> {
@@ -73,90 +109,199 @@ impl<'info> Buy<'info> {
         &mut self,
         city_name: String,
         lamports: u64,
-        circle_rate: u64,
-        sol_price_usd: u64,
-        vault_bump: u8,
-        metadata_uri: String,
+        min_tokens_out: u64,
+        deadline: Option<i64>,
     ) -> Result<()> {
-        require!(circle_rate > 0, RwaError::RateNotValid);
-        require!(sol_price_usd > 0, RwaError::RateNotValid);
         require!(lamports > 0, RwaError::InvalidAmount);
 
-        let is_new_city = self.city_config.mint == Pubkey::default();
-
-        if is_new_city {
-            self.city_config.mint = self.city_mint.key();
-            self.city_config.city_name = city_name.clone();
-            self.city_config.total_supply = 0;
-            self.city_config.metadata_uri = metadata_uri.clone();
-            msg!("NEW CITY TOKEN CREATED: {}", city_name);
-            msg!("   Mint address: {}", self.city_mint.key());
-            msg!("   Metadata URI: {}", metadata_uri);
-        } else {
-            msg!("Buying existing city token: {}", city_name);
-            msg!("   Mint address: {}", self.city_mint.key());
-        }
+        let quote = self.quote(deadline)?;
+
+        // The fee is taken from the lamports paid; the rest backs the tokens
+        let fee_lamports = fee_amount(lamports, quote.fee_bps)?;
+        let net_lamports = lamports
+            .checked_sub(fee_lamports)
+            .ok_or(RwaError::InsufficientFunds)?;
+
+        // tokens = lamports * sol_price * 10^decimals / (10^9 * rate)
+        let token_amount_with_decimals =
+            lamports_to_tokens(net_lamports, quote.sol_price_usd, quote.city_price_usd)?;
+        require!(token_amount_with_decimals > 0, RwaError::InvalidAmount);
+        require!(
+            token_amount_with_decimals >= min_tokens_out,
+            RwaError::SlippageExceeded
+        );
 
-        // Multiply first, then divide to preserve precision
-        let sol_amount_usd = lamports
-            .checked_mul(sol_price_usd)
-            .and_then(|v| v.checked_div(1_000_000_000))
-            .ok_or(RwaError::DivideByZero)?;
+        msg!("Buying city token: {}", city_name);
+        self.settle(
+            quote,
+            net_lamports,
+            fee_lamports,
+            token_amount_with_decimals,
+        )
+    }
+
+    pub fn buy_exact_out(
+        &mut self,
+        city_name: String,
+        token_amount: u64,
+        max_lamports_in: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        require!(token_amount > 0, RwaError::InvalidAmount);
 
-        // Apply decimals BEFORE dividing to preserve precision
-        // tokens = (USD value * 1_000_000) / rate
-        let token_amount_with_decimals = sol_amount_usd
-            .checked_mul(1_000_000)
-            .and_then(|v| v.checked_div(circle_rate))
-            .ok_or(RwaError::DivideByZero)?;
+        let quote = self.quote(deadline)?;
+
+        // Both roundings go up so the vault is never short for the tokens minted
+        let net_lamports =
+            tokens_to_lamports_rounded_up(token_amount, quote.sol_price_usd, quote.city_price_usd)?;
+        require!(net_lamports > 0, RwaError::InvalidAmount);
+
+        // Gross up so that the fee on the total is covered:
+        // lamports = ceil(net * 10_000 / (10_000 - fee_bps))
+        let fee_denominator = BPS_DENOMINATOR - quote.fee_bps as u64;
+        let lamports =
+            (net_lamports as u128 * BPS_DENOMINATOR as u128).div_ceil(fee_denominator as u128);
+        let lamports = u64::try_from(lamports).map_err(|_| error!(RwaError::Overflow))?;
+        let fee_lamports = lamports - net_lamports;
+
+        require!(lamports <= max_lamports_in, RwaError::SlippageExceeded);
+
+        msg!("Buying exact amount of city token: {}", city_name);
+        self.settle(quote, net_lamports, fee_lamports, token_amount)
+    }
+
+    fn quote(&self, deadline: Option<i64>) -> Result<BuyQuote> {
+        self.protocol_config.require_feature(FEATURE_TRADING)?;
+
+        let city_config: CityConfig =
+            load_account(&self.city_config.to_account_info(), RwaError::CityNotFound)?;
+        require!(
+            city_config.mint == self.city_mint.key(),
+            RwaError::InvalidMint
+        );
+        require!(city_config.status.can_buy(), RwaError::CityBuyDisabled);
+
+        let city_price: CityPrice = load_account(
+            &self.city_price.to_account_info(),
+            RwaError::InvalidPriceFeed,
+        )?;
+
+        let config = &self.protocol_config;
+        let now = Clock::get()?.unix_timestamp;
+        check_deadline(deadline, now)?;
+        // Minting skips the transfer hook, so the city's rules apply here
+        let kyc = require_kyc(&self.kyc_record.to_account_info(), now)?;
+        self.jurisdiction_rules.require_allowed(kyc.jurisdiction)?;
+        let sol_price_usd = OraclePrice::from_pyth(&self.sol_usd_price.to_account_info())?
+            .validated(now, config.max_price_age, config.max_confidence_bps)?;
+        let city_price_usd = city_price.oracle_price().validated(
+            now,
+            config.max_city_price_age,
+            config.max_confidence_bps,
+        )?;
+        let fee_bps = city_config.buy_fee_bps(config);
+
+        Ok(BuyQuote {
+            city_config,
+            sol_price_usd,
+            city_price_usd,
+            fee_bps,
+        })
+    }
+
+    /// Collects `net_lamports` into the vault and the fee into the treasury,
+    /// then mints `token_amount` to the user.
+    fn settle(
+        &mut self,
+        quote: BuyQuote,
+        net_lamports: u64,
+        fee_lamports: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        let BuyQuote {
+            mut city_config,
+            sol_price_usd,
+            city_price_usd,
+            fee_bps,
+        } = quote;
+        let lamports = net_lamports
+            .checked_add(fee_lamports)
+            .ok_or(RwaError::Overflow)?;
+
+        // Prices carry PRICE_DECIMALS, so the USD value is in micro-dollars
+        let sol_amount_usd = (net_lamports as u128 * sol_price_usd as u128 / 1_000_000_000) as u64;
 
         // Calculate sol units for logging
-        let sol_units = lamports
-            .checked_div(1_000_000_000)
-            .unwrap_or(0);
+        let sol_units = lamports.checked_div(1_000_000_000).unwrap_or(0);
 
+        msg!("   Mint address: {}", self.city_mint.key());
         msg!("   PURCHASE DETAILS:");
         msg!("   User: {}", self.user.key());
-        msg!("   City: {}", city_name);
+        msg!("   City: {}", city_config.city_name);
         msg!("   Lamports paid: {}", lamports);
         msg!("   SOL amount: {}.{}", sol_units, lamports % 1_000_000_000);
-        msg!("   USD value: ${}", sol_amount_usd);
-        msg!("   Rate per token: ${}", circle_rate);
-        msg!(
-            "   Tokens to mint: {} (with decimals)",
-            token_amount_with_decimals
-        );
+        msg!("   Fee: {} lamports ({} bps)", fee_lamports, fee_bps);
+        msg!("   SOL price: {} micro-USD", sol_price_usd);
+        msg!("   USD value: {} micro-USD", sol_amount_usd);
+        msg!("   Rate per token: {} micro-USD", city_price_usd);
+        msg!("   Tokens to mint: {} (with decimals)", token_amount);
 
-        anchor_lang::solana_program::program::invoke(
-            &anchor_lang::solana_program::system_instruction::transfer(
-                &self.user.key(),
-                &self.vault.key(),
-                lamports,
-            ),
-            &[
-                self.user.to_account_info(),
-                self.vault.to_account_info(),
-                self.system_program.to_account_info(),
-            ],
+        deposit_to_vault(
+            &mut self.vault,
+            &self.user.to_account_info(),
+            &self.system_program.to_account_info(),
+            net_lamports,
         )?;
-
-        self.vault.balance = self
+        self.vault.liabilities = self
             .vault
-            .balance
-            .checked_add(lamports)
+            .liabilities
+            .checked_add(net_lamports)
             .ok_or(RwaError::Overflow)?;
 
-        self.city_config.total_supply = self
-            .city_config
-            .total_supply
-            .checked_add(token_amount_with_decimals)
-            .ok_or(RwaError::Overflow)?;
+        if fee_lamports > 0 {
+            anchor_lang::solana_program::program::invoke(
+                &anchor_lang::solana_program::system_instruction::transfer(
+                    &self.user.key(),
+                    &self.fee_treasury.key(),
+                    fee_lamports,
+                ),
+                &[
+                    self.user.to_account_info(),
+                    self.fee_treasury.to_account_info(),
+                    self.system_program.to_account_info(),
+                ],
+            )?;
+
+            let treasury = &mut self.fee_treasury;
+            treasury.balance = treasury
+                .balance
+                .checked_add(fee_lamports)
+                .ok_or(RwaError::Overflow)?;
+            treasury.total_collected = treasury
+                .total_collected
+                .checked_add(fee_lamports)
+                .ok_or(RwaError::Overflow)?;
+        }
+
+        city_config.issue(token_amount, net_lamports)?;
+        save_account(&city_config, &self.city_config.to_account_info())?;
 
         msg!("   Vault balance: {} lamports", self.vault.balance);
-        msg!("   Total city supply: {}", self.city_config.total_supply);
+        msg!("   Total city supply: {}", city_config.total_supply);
+
+        associated_token::create_idempotent(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            Create {
+                payer: self.user.to_account_info(),
+                associated_token: self.user_ata.to_account_info(),
+                authority: self.user.to_account_info(),
+                mint: self.city_mint.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            },
+        ))?;
 
-        let binding = self.admin.key();
-        let signer_seeds: &[&[u8]] = &[b"protocol_admin", binding.as_ref(), &[vault_bump]];
+        let signer_seeds = self.vault.signer_seeds();
         let signer = &[&signer_seeds[..]];
 
         let cpi_ctx = CpiContext::new_with_signer(
@@ -168,48 +313,19 @@ impl<'info> Buy<'info> {
             },
             signer,
         );
-        token::mint_to(cpi_ctx, token_amount_with_decimals)?;
+        mint_to(cpi_ctx, token_amount)?;
 
         msg!(" Tokens minted successfully!");
 
-        if is_new_city {
-            msg!("Creating Metaplex metadata for token...");
-
-            let symbol = city_name.chars().take(10).collect::<String>();
-
-            let cpi_program = self.token_metadata_program.to_account_info();
-            let cpi_accounts = CreateMetadataAccountsV3 {
-                metadata: self.metadata.to_account_info(),
-                mint: self.city_mint.to_account_info(),
-                mint_authority: self.vault.to_account_info(),
-                update_authority: self.vault.to_account_info(),
-                payer: self.user.to_account_info(),
-                system_program: self.system_program.to_account_info(),
-                rent: self.rent.to_account_info(),
-            };
-
-            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-
-            let data = mpl_token_metadata::types::DataV2 {
-                name: city_name.clone(),
-                symbol,
-                uri: metadata_uri.clone(),
-                seller_fee_basis_points: 0,
-                creators: None,
-                collection: None,
-                uses: None,
-            };
-
-            create_metadata_accounts_v3(
-                cpi_ctx,
-                data,
-                false,
-                true,
-                None,
-            )?;
-
-            msg!("Metadata created successfully!");
-        }
+        emit!(TokensBought {
+            user: self.user.key(),
+            city_mint: self.city_mint.key(),
+            lamports_paid: lamports,
+            fee_lamports,
+            tokens_minted: token_amount,
+            sol_price_usd,
+            city_price_usd,
+        });
 
         Ok(())
     }
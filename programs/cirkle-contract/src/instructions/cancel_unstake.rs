@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::constants::FEATURE_STAKING;
+use crate::error::RwaError;
+use crate::state::{CityConfig, ProtocolConfig, StakeHistory, StakePool, UserStake};
+use crate::utils::require_kyc;
+
+/// Returns the unbonding bucket to the position's stake, keeping its lock terms.
+#[derive(Accounts)]
+#[instruction(position_id: u64)]
+pub struct CancelUnstake<'info> {
+    pub user: Signer<'info>,
+
+    /// CHECK: Caller's KYC record, loaded so a missing one fails with `KycRequired`
+    #[account(
+        seeds = [b"kyc", user.key().as_ref()],
+        bump,
+    )]
+    pub kyc_record: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub city_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        constraint = city_config.mint == city_mint.key() @ RwaError::InvalidMint,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_mint.key().as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake",
+            user.key().as_ref(),
+            city_mint.key().as_ref(),
+            &position_id.to_le_bytes()
+        ],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-history", user.key().as_ref(), city_mint.key().as_ref()],
+        bump = stake_history.bump,
+    )]
+    pub stake_history: Account<'info, StakeHistory>,
+}
+
+impl<'info> CancelUnstake<'info> {
+    pub fn cancel_unstake(&mut self) -> Result<()> {
+        self.protocol_config.require_feature(FEATURE_STAKING)?;
+        require!(
+            self.city_config.status.can_stake(),
+            RwaError::CityStakeDisabled
+        );
+
+        let user_stake = &mut self.user_stake;
+        let stake_pool = &mut self.stake_pool;
+
+        let amount = user_stake.unbonding_amount;
+        require!(amount > 0, RwaError::NothingUnbonding);
+
+        let now = Clock::get()?.unix_timestamp;
+        require_kyc(&self.kyc_record.to_account_info(), now)?;
+        stake_pool.update(now)?;
+        user_stake.settle(stake_pool, now)?;
+
+        if user_stake.staked_amount == 0 {
+            user_stake.stake_start = now;
+        }
+        user_stake.staked_amount = user_stake
+            .staked_amount
+            .checked_add(amount)
+            .ok_or(RwaError::Overflow)?;
+        user_stake.unbonding_amount = 0;
+        user_stake.unbonding_end = 0;
+        user_stake.sync_reward_debt(stake_pool)?;
+        stake_pool.total_staked = stake_pool
+            .total_staked
+            .checked_add(amount)
+            .ok_or(RwaError::Overflow)?;
+
+        let slot = Clock::get()?.slot;
+        self.stake_history.add(amount, slot)?;
+        stake_pool.checkpoint(slot);
+
+        Ok(())
+    }
+}
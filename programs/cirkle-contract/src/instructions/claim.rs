@@ -1,90 +1,104 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
 
 use crate::error::RwaError;
-use crate::state::{UserStake, Vault};
+use crate::constants::FEATURE_REWARDS;
+use crate::state::{ProtocolConfig, StakePool, UserStake};
+use crate::utils::transfer_checked_with_hook;
 
 #[derive(Accounts)]
+#[instruction(position_id: u64)]
 pub struct ClaimReward<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    /// CHECK: admin wallet
-    pub admin: AccountInfo<'info>,
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub city_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        seeds = [b"protocol_admin", admin.key().as_ref()],
-        bump
+        seeds = [b"stake-pool", city_mint.key().as_ref()],
+        bump = stake_pool.bump,
     )]
-    pub admin_vault: Account<'info, Vault>,
-
-    pub city_mint: Account<'info, Mint>,
+    pub stake_pool: Account<'info, StakePool>,
 
     #[account(
         mut,
         seeds = [
             b"stake",
             user.key().as_ref(),
-            city_mint.key().as_ref()
+            city_mint.key().as_ref(),
+            &position_id.to_le_bytes()
         ],
         bump = user_stake.bump
     )]
     pub user_stake: Account<'info, UserStake>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(address = stake_pool.reward_mint @ RwaError::InvalidRewardMint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = stake_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub reward_pool_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> ClaimReward<'info> {
-    pub fn claim_reward(
-        &mut self,
-        city_price_usd: u64,
-        sol_price_usd: u64,
-    ) -> Result<()> {
-        let user_stake = &mut self.user_stake;
+    pub fn claim_reward(&mut self, hook_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        self.protocol_config.require_feature(FEATURE_REWARDS)?;
 
-        require!(user_stake.staked_amount > 0, RwaError::NothingStaked);
-        require!(city_price_usd > 0, RwaError::InvalidPrice);
-        require!(sol_price_usd > 0, RwaError::InvalidPrice);
+        let user_stake = &mut self.user_stake;
+        let stake_pool = &mut self.stake_pool;
 
         let now = Clock::get()?.unix_timestamp;
-        let seconds_staked = (now - user_stake.stake_start).max(0) as u64;
-
-        let seconds_per_year: u64 = 31_536_000;
-
-        let city_value_usd = user_stake
-            .staked_amount
-            .checked_mul(city_price_usd)
-            .unwrap();
-
-        let city_value_sol = city_value_usd.checked_div(sol_price_usd).unwrap();
-
-        let reward = city_value_sol
-            .checked_mul(seconds_staked)
-            .unwrap()
-            .checked_mul(6)
-            .unwrap()
-            .checked_div(100)
-            .unwrap()
-            .checked_div(seconds_per_year)
-            .unwrap();
+        stake_pool.update(now)?;
+        user_stake.settle(stake_pool, now)?;
 
+        // An underfunded pool pays what it holds and the rest stays pending
+        let reward = user_stake.pending_rewards.min(stake_pool.reward_balance);
         require!(reward > 0, RwaError::NoRewardsAvailable);
 
-        let vault_account = self.admin_vault.to_account_info();
-        let user_account = self.user.to_account_info();
-
-        **vault_account.lamports.borrow_mut() = vault_account
-            .lamports()
-            .checked_sub(reward)
-            .ok_or(ProgramError::InsufficientFunds)?;
-        **user_account.lamports.borrow_mut() = user_account
-            .lamports()
-            .checked_add(reward)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-
-        user_stake.stake_start = now;
+        let cpi_accounts = TransferChecked {
+            from: self.reward_pool_ata.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.user_reward_ata.to_account_info(),
+            authority: stake_pool.to_account_info(),
+        };
+
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts,
+                &[&stake_pool.signer_seeds()],
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            reward,
+            self.reward_mint.decimals,
+        )?;
+
+        user_stake.take_rewards(stake_pool, reward);
 
         Ok(())
     }
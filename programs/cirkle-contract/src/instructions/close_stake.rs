@@ -1,44 +1,103 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token_interface::{
+    close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::error::RwaError;
+use crate::state::UserStake;
+use crate::utils::transfer_checked_with_hook;
 
 #[derive(Accounts)]
+#[instruction(position_id: u64)]
 pub struct CloseStake<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    ///CHECK:city mint
-    pub city_mint: UncheckedAccount<'info>,
+    pub city_mint: InterfaceAccount<'info, Mint>,
 
-    ///CHECK:USER STAKE
     #[account(
         mut,
+        close = user,
         seeds = [
             b"stake",
             user.key().as_ref(),
-            city_mint.key().as_ref()
+            city_mint.key().as_ref(),
+            &position_id.to_le_bytes()
         ],
-        bump
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(
+        mut,
+        associated_token::mint = city_mint,
+        associated_token::authority = user_stake,
+        associated_token::token_program = token_program,
+    )]
+    pub stake_vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_city_ata.mint == city_mint.key(),
+        constraint = user_city_ata.owner == user.key(),
     )]
-    pub user_stake: UncheckedAccount<'info>,
+    pub user_city_ata: InterfaceAccount<'info, TokenAccount>,
 
-    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 impl<'info> CloseStake<'info> {
-    pub fn close_stake(&self) -> Result<()> {
-        let lamports = self.user_stake.lamports();
+    pub fn close_stake(&self, hook_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let user_stake = &self.user_stake;
 
-        let cpi_accounts = Transfer {
-            from: self.user_stake.to_account_info(),
-            to: self.user.to_account_info(),
-        };
-
-        let cpi_ctx = CpiContext::new(
-            self.system_program.to_account_info(),
-            cpi_accounts,
+        require!(
+            user_stake.staked_amount == 0 && user_stake.unbonding_amount == 0,
+            RwaError::PositionNotEmpty
         );
+        require!(user_stake.pending_rewards == 0, RwaError::RewardsUnclaimed);
+
+        let binding = self.city_mint.key();
+        let position_id = user_stake.position_id.to_le_bytes();
+        let stake_seeds: &[&[u8]] = &[
+            b"stake",
+            self.user.key.as_ref(),
+            binding.as_ref(),
+            &position_id,
+            &[user_stake.bump],
+        ];
+
+        // Tokens sent to the vault outside of staking would block the close
+        if self.stake_vault_ata.amount > 0 {
+            let cpi_sweep = TransferChecked {
+                from: self.stake_vault_ata.to_account_info(),
+                mint: self.city_mint.to_account_info(),
+                to: self.user_city_ata.to_account_info(),
+                authority: user_stake.to_account_info(),
+            };
+
+            transfer_checked_with_hook(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    cpi_sweep,
+                    &[stake_seeds],
+                )
+                .with_remaining_accounts(hook_accounts.to_vec()),
+                self.stake_vault_ata.amount,
+                self.city_mint.decimals,
+            )?;
+        }
+
+        let cpi_close = CloseAccount {
+            account: self.stake_vault_ata.to_account_info(),
+            destination: self.user.to_account_info(),
+            authority: user_stake.to_account_info(),
+        };
 
-        system_program::transfer(cpi_ctx, lamports)?;
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_close,
+            &[stake_seeds],
+        ))?;
 
         Ok(())
     }
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::constants::FEATURE_STAKING;
+use crate::error::RwaError;
+use crate::state::{ProtocolConfig, UserStake};
+use crate::utils::transfer_checked_with_hook;
+
+#[derive(Accounts)]
+#[instruction(position_id: u64)]
+pub struct CompleteUnstake<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub city_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_city_ata.mint == city_mint.key(),
+        constraint = user_city_ata.owner == user.key(),
+    )]
+    pub user_city_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake",
+            user.key().as_ref(),
+            city_mint.key().as_ref(),
+            &position_id.to_le_bytes()
+        ],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(
+        mut,
+        associated_token::mint = city_mint,
+        associated_token::authority = user_stake,
+        associated_token::token_program = token_program,
+    )]
+    pub stake_vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CompleteUnstake<'info> {
+    pub fn complete_unstake(&mut self, hook_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        self.protocol_config.require_feature(FEATURE_STAKING)?;
+
+        let user_stake = &mut self.user_stake;
+
+        let amount = user_stake.unbonding_amount;
+        require!(amount > 0, RwaError::NothingUnbonding);
+        require!(
+            Clock::get()?.unix_timestamp >= user_stake.unbonding_end,
+            RwaError::UnbondingNotFinished
+        );
+
+        let binding = self.city_mint.key();
+        let position_id = user_stake.position_id.to_le_bytes();
+        let stake_seeds: &[&[u8]] = &[
+            b"stake",
+            self.user.key.as_ref(),
+            binding.as_ref(),
+            &position_id,
+            &[user_stake.bump],
+        ];
+
+        let cpi_unstake = TransferChecked {
+            from: self.stake_vault_ata.to_account_info(),
+            mint: self.city_mint.to_account_info(),
+            to: self.user_city_ata.to_account_info(),
+            authority: user_stake.to_account_info(),
+        };
+
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_unstake,
+                &[stake_seeds],
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            self.city_mint.decimals,
+        )?;
+
+        user_stake.unbonding_amount = 0;
+        user_stake.unbonding_end = 0;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,315 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_interface::{
+    close_account, mint_to, CloseAccount, Mint, MintTo, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use crate::constants::{FEATURE_REWARDS, FEATURE_STAKING};
+use crate::error::RwaError;
+use crate::state::{
+    CityConfig, CityPrice, JurisdictionRules, ProtocolConfig, StakeHistory, StakePool, UserStake,
+    Vault,
+};
+use crate::utils::{
+    pay_from_vault, require_kyc, require_reserve_ratio, tokens_to_lamports_rounded_up,
+    transfer_checked_with_hook, value_to_tokens, OraclePrice,
+};
+
+/// Restakes a position's pending rewards without changing its lock terms.
+///
+/// City-token rewards move straight into the position. Rewards in another
+/// mint buy newly minted city tokens at the current validated price: wrapped
+/// SOL is unwrapped into the vault to back them, and other tokens are kept in
+/// the vault's own token account.
+#[derive(Accounts)]
+#[instruction(position_id: u64)]
+pub struct Compound<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Caller's KYC record, loaded so a missing one fails with `KycRequired`
+    #[account(
+        seeds = [b"kyc", user.key().as_ref()],
+        bump,
+    )]
+    pub kyc_record: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = vault @ RwaError::InvalidVault,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    #[account(mut)]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: Pyth SOL/USD price account, parsed in `compound`
+    #[account(address = protocol_config.sol_usd_feed @ RwaError::InvalidPriceFeed)]
+    pub sol_usd_price: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"city-price", city_config.city_name.as_bytes()],
+        bump = city_price.bump,
+    )]
+    pub city_price: Box<Account<'info, CityPrice>>,
+
+    #[account(mut)]
+    pub city_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"jurisdiction-rules", city_mint.key().as_ref()],
+        bump = jurisdiction_rules.bump,
+    )]
+    pub jurisdiction_rules: Box<Account<'info, JurisdictionRules>>,
+
+    #[account(
+        mut,
+        constraint = city_config.mint == city_mint.key() @ RwaError::InvalidMint,
+    )]
+    pub city_config: Box<Account<'info, CityConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_mint.key().as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Box<Account<'info, StakePool>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake",
+            user.key().as_ref(),
+            city_mint.key().as_ref(),
+            &position_id.to_le_bytes()
+        ],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Box<Account<'info, UserStake>>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-history", user.key().as_ref(), city_mint.key().as_ref()],
+        bump = stake_history.bump,
+    )]
+    pub stake_history: Box<Account<'info, StakeHistory>>,
+
+    #[account(
+        mut,
+        associated_token::mint = city_mint,
+        associated_token::authority = user_stake,
+        associated_token::token_program = token_program,
+    )]
+    pub stake_vault_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = stake_pool.reward_mint @ RwaError::InvalidRewardMint)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = stake_pool,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub reward_pool_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Vault's account for reward tokens it is paid in; only needed when
+    /// rewards are not in the city token
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub vault_reward_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Program of the reward mint, which need not be Token-2022
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Compound<'info> {
+    pub fn compound(&mut self, hook_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let config = &self.protocol_config;
+        config.require_feature(FEATURE_STAKING)?;
+        config.require_feature(FEATURE_REWARDS)?;
+        require!(
+            self.city_config.status.can_stake(),
+            RwaError::CityStakeDisabled
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let kyc = require_kyc(&self.kyc_record.to_account_info(), now)?;
+        self.jurisdiction_rules.require_allowed(kyc.jurisdiction)?;
+
+        self.stake_pool.update(now)?;
+        self.user_stake.settle(&mut self.stake_pool, now)?;
+
+        let amount = self
+            .user_stake
+            .pending_rewards
+            .min(self.stake_pool.reward_balance);
+        require!(amount > 0, RwaError::NoRewardsAvailable);
+
+        let tokens = if self.reward_mint.key() == self.city_mint.key() {
+            let stake_vault_ata = self.stake_vault_ata.to_account_info();
+            self.pay_rewards(stake_vault_ata, amount, hook_accounts)?;
+            amount
+        } else {
+            self.mint_for_rewards(amount, now, hook_accounts)?
+        };
+        self.user_stake.take_rewards(&mut self.stake_pool, amount);
+
+        let user_stake = &mut self.user_stake;
+        let stake_pool = &mut self.stake_pool;
+        user_stake.staked_amount = user_stake
+            .staked_amount
+            .checked_add(tokens)
+            .ok_or(RwaError::Overflow)?;
+        user_stake.sync_reward_debt(stake_pool)?;
+        stake_pool.total_staked = stake_pool
+            .total_staked
+            .checked_add(tokens)
+            .ok_or(RwaError::Overflow)?;
+
+        let slot = Clock::get()?.slot;
+        self.stake_history.add(tokens, slot)?;
+        stake_pool.checkpoint(slot);
+
+        msg!(
+            "Compounded {} reward units into {} city tokens",
+            amount,
+            tokens
+        );
+
+        Ok(())
+    }
+
+    /// Moves `amount` reward tokens out of the pool's reward account.
+    fn pay_rewards(
+        &self,
+        to: AccountInfo<'info>,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.reward_pool_ata.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to,
+            authority: self.stake_pool.to_account_info(),
+        };
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.reward_token_program.to_account_info(),
+                cpi_accounts,
+                &[&self.stake_pool.signer_seeds()],
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            self.reward_mint.decimals,
+        )
+    }
+
+    /// Hands `amount` reward tokens to the vault and mints the city tokens
+    /// they are worth into the stake vault, returning how many were minted.
+    fn mint_for_rewards(
+        &mut self,
+        amount: u64,
+        now: i64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        require!(
+            self.city_config.status.can_buy(),
+            RwaError::CityStakeDisabled
+        );
+
+        let config = &self.protocol_config;
+        let sol_price_usd = OraclePrice::from_pyth(&self.sol_usd_price.to_account_info())?
+            .validated(now, config.max_price_age, config.max_confidence_bps)?;
+        let city_price_usd = self.city_price.oracle_price().validated(
+            now,
+            config.max_city_price_age,
+            config.max_confidence_bps,
+        )?;
+
+        let reward_price_usd = self.reward_price_usd(sol_price_usd)?;
+        let tokens = value_to_tokens(
+            amount,
+            self.reward_mint.decimals,
+            reward_price_usd,
+            city_price_usd,
+        )?;
+        require!(tokens > 0, RwaError::InvalidAmount);
+        let lamports = tokens_to_lamports_rounded_up(tokens, sol_price_usd, city_price_usd)?;
+
+        let vault_reward_ata = self
+            .vault_reward_ata
+            .as_ref()
+            .ok_or(RwaError::InvalidRewardMint)?
+            .to_account_info();
+        self.pay_rewards(vault_reward_ata.clone(), amount, hook_accounts)?;
+
+        if self.reward_mint.key() == native_mint::ID {
+            // Unwrap into the vault so the SOL backs the new tokens, and
+            // give the user back the rent of the account they opened
+            let rent = Rent::get()?.minimum_balance(vault_reward_ata.data_len());
+            let signer_seeds = self.vault.signer_seeds();
+            close_account(CpiContext::new_with_signer(
+                self.reward_token_program.to_account_info(),
+                CloseAccount {
+                    account: vault_reward_ata,
+                    destination: self.vault.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                &[&signer_seeds[..]],
+            ))?;
+            pay_from_vault(&mut self.vault, &self.user.to_account_info(), rent)?;
+        }
+
+        // New tokens are liabilities like bought ones; the vault must still
+        // meet its reserve ratio
+        self.city_config.issue(tokens, lamports)?;
+        self.vault.liabilities = self
+            .vault
+            .liabilities
+            .checked_add(lamports)
+            .ok_or(RwaError::Overflow)?;
+        require_reserve_ratio(&self.vault, self.protocol_config.min_reserve_ratio_bps)?;
+
+        let signer_seeds = self.vault.signer_seeds();
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.city_mint.to_account_info(),
+                    to: self.stake_vault_ata.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                &[&signer_seeds[..]],
+            ),
+            tokens,
+        )?;
+
+        Ok(tokens)
+    }
+
+    /// USD price of one whole reward token: SOL/USD for wrapped SOL,
+    /// otherwise the price the admin set for the pool.
+    fn reward_price_usd(&self, sol_price_usd: u64) -> Result<u64> {
+        if self.reward_mint.key() == native_mint::ID {
+            Ok(sol_price_usd)
+        } else {
+            require!(
+                self.stake_pool.reward_price_usd > 0,
+                RwaError::RewardPriceNotSet
+            );
+            Ok(self.stake_pool.reward_price_usd)
+        }
+    }
+}
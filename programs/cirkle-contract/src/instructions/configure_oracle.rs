@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::RwaError;
+use crate::state::ProtocolConfig;
+
+#[derive(Accounts)]
+pub struct ConfigureOracle<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+impl<'info> ConfigureOracle<'info> {
+    pub fn configure_oracle(
+        &mut self,
+        sol_usd_feed: Pubkey,
+        max_price_age: u64,
+        max_confidence_bps: u16,
+        max_city_price_age: u64,
+    ) -> Result<()> {
+        require!(
+            sol_usd_feed != Pubkey::default(),
+            RwaError::InvalidPriceFeed
+        );
+        require!(
+            max_confidence_bps as u64 <= BPS_DENOMINATOR,
+            RwaError::AmountNotValid
+        );
+
+        let config = &mut self.protocol_config;
+        config.sol_usd_feed = sol_usd_feed;
+        config.max_price_age = max_price_age;
+        config.max_confidence_bps = max_confidence_bps;
+        config.max_city_price_age = max_city_price_age;
+
+        msg!("Oracle configured: SOL/USD feed {}", sol_usd_feed);
+        msg!("   Max price age: {}s", max_price_age);
+        msg!("   Max confidence: {} bps", max_confidence_bps);
+        msg!("   Max city price age: {}s", max_city_price_age);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{token_metadata_initialize, Mint, TokenMetadataInitialize},
+};
+
+use crate::constants::{
+    DEFAULT_REWARD_APR_BPS, DEFAULT_UNBONDING_PERIOD, TRANSFER_HOOK_PROGRAM_ID,
+};
+use crate::error::RwaError;
+use crate::state::{CityConfig, CityStatus, JurisdictionRules, ProtocolConfig, StakePool, Vault};
+use crate::utils::top_up_rent;
+
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct CreateCity<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+        has_one = vault @ RwaError::InvalidVault,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub vault: Account<'info, Vault>,
+
+    /// City configuration - stores metadata about the city's token
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump,
+        space = 8 + CityConfig::INIT_SPACE,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    /// City-specific Token-2022 mint - every transfer runs the compliance hook
+    /// and the token metadata lives in the mint itself
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"city-mint", city_name.as_bytes()],
+        bump,
+        mint::decimals = 6,
+        mint::authority = vault,
+        mint::freeze_authority = vault,
+        mint::token_program = token_program,
+        extensions::transfer_hook::authority = vault,
+        extensions::transfer_hook::program_id = TRANSFER_HOOK_PROGRAM_ID,
+        extensions::metadata_pointer::authority = vault,
+        extensions::metadata_pointer::metadata_address = city_mint,
+    )]
+    pub city_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Staking reward index for the city
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"stake-pool", city_mint.key().as_ref()],
+        bump,
+        space = 8 + StakePool::INIT_SPACE,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    /// Jurisdictions the transfer hook rejects for this city
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"jurisdiction-rules", city_mint.key().as_ref()],
+        bump,
+        space = 8 + JurisdictionRules::INIT_SPACE,
+    )]
+    pub jurisdiction_rules: Account<'info, JurisdictionRules>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateCity<'info> {
+    pub fn create_city(
+        &mut self,
+        city_name: String,
+        metadata_uri: String,
+        bump: u8,
+        pool_bump: u8,
+        rules_bump: u8,
+    ) -> Result<()> {
+        require!(
+            !city_name.is_empty() && city_name.len() <= 32,
+            RwaError::InvalidCityName
+        );
+        require!(metadata_uri.len() <= 256, RwaError::InvalidMetadataUri);
+
+        self.city_config.set_inner(CityConfig {
+            city_name: city_name.clone(),
+            mint: self.city_mint.key(),
+            total_supply: 0,
+            bump,
+            metadata_uri: metadata_uri.clone(),
+            status: CityStatus::Active,
+            buy_fee_bps: None,
+            sell_fee_bps: None,
+            liabilities: 0,
+        });
+
+        self.stake_pool.set_inner(StakePool {
+            city_mint: self.city_mint.key(),
+            total_staked: 0,
+            acc_reward_per_token: 0,
+            last_update: Clock::get()?.unix_timestamp,
+            bump: pool_bump,
+            reward_rate_bps: DEFAULT_REWARD_APR_BPS,
+            last_rate_change: 0,
+            early_exit_penalty_bps: None,
+            reward_mint: self.city_mint.key(),
+            reward_price_usd: 0,
+            reward_balance: 0,
+            unclaimed_rewards: 0,
+            penalty_balance: 0,
+            total_weight: 0,
+            acc_yield_per_token: 0,
+            unbonding_period: DEFAULT_UNBONDING_PERIOD,
+            checkpoints: Vec::new(),
+        });
+
+        self.jurisdiction_rules.set_inner(JurisdictionRules {
+            city_mint: self.city_mint.key(),
+            blocked: Vec::new(),
+            updated_at: Clock::get()?.unix_timestamp,
+            bump: rules_bump,
+        });
+
+        msg!("NEW CITY TOKEN CREATED: {}", city_name);
+        msg!("   Mint address: {}", self.city_mint.key());
+        msg!("   Metadata URI: {}", metadata_uri);
+
+        let signer_seeds = self.vault.signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        let symbol = city_name.chars().take(10).collect::<String>();
+
+        let cpi_accounts = TokenMetadataInitialize {
+            program_id: self.token_program.to_account_info(),
+            metadata: self.city_mint.to_account_info(),
+            update_authority: self.vault.to_account_info(),
+            mint_authority: self.vault.to_account_info(),
+            mint: self.city_mint.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer);
+
+        token_metadata_initialize(cpi_ctx, city_name, symbol, metadata_uri)?;
+
+        top_up_rent(
+            &self.city_mint.to_account_info(),
+            &self.admin.to_account_info(),
+            &self.system_program.to_account_info(),
+        )?;
+
+        msg!("Metadata created successfully!");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    freeze_account, thaw_account, FreezeAccount, Mint, ThawAccount, TokenAccount, TokenInterface,
+};
+
+use crate::error::RwaError;
+use crate::events::HolderFreezeChanged;
+use crate::state::{CityConfig, FreezeLog, FreezeRecord, ProtocolConfig, Vault};
+
+/// Freezes or thaws a holder's city-token account with the vault's freeze
+/// authority. Either the admin or the compliance authority may sign.
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct SetHolderFrozen<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == protocol_config.admin
+            || authority.key() == protocol_config.compliance_authority @ RwaError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = vault @ RwaError::InvalidVault,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(address = city_config.mint @ RwaError::InvalidMint)]
+    pub city_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = city_mint,
+        token::token_program = token_program,
+    )]
+    pub holder_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + FreezeLog::INIT_SPACE,
+        seeds = [b"freeze-log", holder_ata.key().as_ref()],
+        bump,
+    )]
+    pub freeze_log: Account<'info, FreezeLog>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + FreezeRecord::INIT_SPACE,
+        seeds = [
+            b"freeze",
+            holder_ata.key().as_ref(),
+            &freeze_log.count.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetHolderFrozen<'info> {
+    pub fn set_holder_frozen(
+        &mut self,
+        frozen: bool,
+        reason_code: u16,
+        log_bump: u8,
+        bump: u8,
+    ) -> Result<()> {
+        let signer_seeds = self.vault.signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        if frozen {
+            let cpi_accounts = FreezeAccount {
+                account: self.holder_ata.to_account_info(),
+                mint: self.city_mint.to_account_info(),
+                authority: self.vault.to_account_info(),
+            };
+            freeze_account(CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            ))?;
+        } else {
+            let cpi_accounts = ThawAccount {
+                account: self.holder_ata.to_account_info(),
+                mint: self.city_mint.to_account_info(),
+                authority: self.vault.to_account_info(),
+            };
+            thaw_account(CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            ))?;
+        }
+
+        let freeze_log = &mut self.freeze_log;
+        let sequence = freeze_log.count;
+        freeze_log.token_account = self.holder_ata.key();
+        freeze_log.count = sequence.checked_add(1).ok_or(RwaError::Overflow)?;
+        freeze_log.bump = log_bump;
+
+        let now = Clock::get()?.unix_timestamp;
+        self.freeze_record.set_inner(FreezeRecord {
+            token_account: self.holder_ata.key(),
+            owner: self.holder_ata.owner,
+            city_mint: self.city_mint.key(),
+            sequence,
+            frozen,
+            reason_code,
+            authority: self.authority.key(),
+            updated_at: now,
+            bump,
+        });
+
+        emit!(HolderFreezeChanged {
+            token_account: self.holder_ata.key(),
+            owner: self.holder_ata.owner,
+            city_mint: self.city_mint.key(),
+            sequence,
+            frozen,
+            reason_code,
+            authority: self.authority.key(),
+        });
+
+        Ok(())
+    }
+}
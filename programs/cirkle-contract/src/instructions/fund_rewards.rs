@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::RwaError;
+use crate::state::{CityConfig, ProtocolConfig, StakePool};
+use crate::utils::transfer_checked_with_hook;
+
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct FundRewards<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_config.mint.as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(address = stake_pool.reward_mint @ RwaError::InvalidRewardMint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = admin_reward_ata.mint == reward_mint.key() @ RwaError::InvalidRewardMint,
+        constraint = admin_reward_ata.owner == admin.key(),
+    )]
+    pub admin_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = reward_mint,
+        associated_token::authority = stake_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub reward_pool_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FundRewards<'info> {
+    pub fn fund_rewards(
+        &mut self,
+        city_name: String,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        self.deposit(amount, hook_accounts)?;
+
+        msg!("Rewards funded: {}", city_name);
+        msg!("   Amount: {}, pool balance: {}", amount, self.stake_pool.reward_balance);
+
+        Ok(())
+    }
+
+    /// Deposits rental income and distributes it pro-rata to current stakers.
+    /// Pays out in the pool's reward mint, e.g. wrapped SOL or a stablecoin.
+    pub fn deposit_yield(
+        &mut self,
+        city_name: String,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        self.deposit(amount, hook_accounts)?;
+
+        // Close out the fixed rate before raising the index
+        let stake_pool = &mut self.stake_pool;
+        stake_pool.update(Clock::get()?.unix_timestamp)?;
+        stake_pool.distribute(amount)?;
+
+        msg!("Yield deposited: {}", city_name);
+        msg!("   Amount: {}, pool balance: {}", amount, stake_pool.reward_balance);
+
+        Ok(())
+    }
+
+    fn deposit(&mut self, amount: u64, hook_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        require!(amount > 0, RwaError::InvalidAmount);
+
+        let cpi_accounts = TransferChecked {
+            from: self.admin_reward_ata.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.reward_pool_ata.to_account_info(),
+            authority: self.admin.to_account_info(),
+        };
+
+        transfer_checked_with_hook(
+            CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+                .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            self.reward_mint.decimals,
+        )?;
+
+        let stake_pool = &mut self.stake_pool;
+        stake_pool.reward_balance = stake_pool
+            .reward_balance
+            .checked_add(amount)
+            .ok_or(RwaError::Overflow)?;
+
+        Ok(())
+    }
+}
@@ -1,14 +1,32 @@
 use anchor_lang::prelude::*;
 
-use crate::state::Vault;
+use crate::constants::{
+    DEFAULT_MAX_CITY_PRICE_AGE, DEFAULT_MAX_CONFIDENCE_BPS, DEFAULT_MAX_PRICE_AGE,
+    DEFAULT_MIN_RESERVE_RATIO_BPS, FEATURES_ALL,
+};
+use crate::error::RwaError;
+use crate::program::CirkleContract;
+use crate::state::{FeeTreasury, ProtocolConfig, Vault};
 
 #[derive(Accounts)]
 pub struct AdminVault<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
 
+    /// Only the program's upgrade authority may appoint the admin
+    pub upgrade_authority: Signer<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, CirkleContract>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key())
+            @ RwaError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
     #[account(
-        init_if_needed,
+        init,
         payer= admin,
         space = Vault::INIT_SPACE + 8,
         seeds = [b"protocol_admin",admin.key().as_ref()],
@@ -16,17 +34,60 @@ pub struct AdminVault<'info> {
     )]
     pub admin_vault: Account<'info, Vault>,
 
+    /// Protocol singleton recording the canonical admin
+    #[account(
+        init,
+        payer = admin,
+        space = ProtocolConfig::INIT_SPACE + 8,
+        seeds = [b"protocol-config"],
+        bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = FeeTreasury::INIT_SPACE + 8,
+        seeds = [b"fee-treasury"],
+        bump,
+    )]
+    pub fee_treasury: Account<'info, FeeTreasury>,
+
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> AdminVault<'info> {
-    pub fn create_vault(&mut self, bump: u8) -> Result<()> {
+    pub fn create_vault(&mut self, bump: u8, config_bump: u8, treasury_bump: u8) -> Result<()> {
         let vault = &mut self.admin_vault;
 
         vault.set_inner(Vault {
             authority: *self.admin.key,
             balance: 0,
             bump,
+            liabilities: 0,
+        });
+
+        self.protocol_config.set_inner(ProtocolConfig {
+            admin: *self.admin.key,
+            guardian: *self.admin.key,
+            vault: vault.key(),
+            sol_usd_feed: Pubkey::default(),
+            max_price_age: DEFAULT_MAX_PRICE_AGE,
+            max_confidence_bps: DEFAULT_MAX_CONFIDENCE_BPS,
+            buy_fee_bps: 0,
+            sell_fee_bps: 0,
+            min_reserve_ratio_bps: DEFAULT_MIN_RESERVE_RATIO_BPS,
+            features: FEATURES_ALL,
+            paused: 0,
+            bump: config_bump,
+            compliance_authority: *self.admin.key,
+            max_city_price_age: DEFAULT_MAX_CITY_PRICE_AGE,
+        });
+
+        self.fee_treasury.set_inner(FeeTreasury {
+            balance: 0,
+            total_collected: 0,
+            bump: treasury_bump,
         });
 
         Ok(())
@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+use crate::state::{
+    CityConfig, JurisdictionRules, KycRecord, KycStatus, ProtocolConfig, MAX_BLOCKED_JURISDICTIONS,
+};
+
+#[derive(Accounts)]
+pub struct IssueKyc<'info> {
+    #[account(mut)]
+    pub compliance_authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = compliance_authority @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// CHECK: the wallet being verified; only its key is stored
+    pub wallet: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = compliance_authority,
+        space = 8 + KycRecord::INIT_SPACE,
+        seeds = [b"kyc", wallet.key().as_ref()],
+        bump,
+    )]
+    pub kyc_record: Account<'info, KycRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateKyc<'info> {
+    pub compliance_authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = compliance_authority @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"kyc", kyc_record.wallet.as_ref()],
+        bump = kyc_record.bump,
+    )]
+    pub kyc_record: Account<'info, KycRecord>,
+}
+
+#[derive(Accounts)]
+pub struct SetComplianceAuthority<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct SetJurisdictionRules<'info> {
+    pub compliance_authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = compliance_authority @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"jurisdiction-rules", city_config.mint.as_ref()],
+        bump = jurisdiction_rules.bump,
+    )]
+    pub jurisdiction_rules: Account<'info, JurisdictionRules>,
+}
+
+fn validate_jurisdiction(jurisdiction: [u8; 2]) -> Result<()> {
+    require!(
+        jurisdiction.iter().all(u8::is_ascii_uppercase),
+        RwaError::InvalidJurisdiction
+    );
+    Ok(())
+}
+
+fn validate_terms(jurisdiction: [u8; 2], expires_at: i64, now: i64) -> Result<()> {
+    validate_jurisdiction(jurisdiction)?;
+    require!(expires_at > now, RwaError::InvalidKycExpiry);
+    Ok(())
+}
+
+impl<'info> IssueKyc<'info> {
+    pub fn issue_kyc(&mut self, jurisdiction: [u8; 2], expires_at: i64, bump: u8) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        validate_terms(jurisdiction, expires_at, now)?;
+
+        self.kyc_record.set_inner(KycRecord {
+            wallet: self.wallet.key(),
+            status: KycStatus::Verified,
+            jurisdiction,
+            expires_at,
+            issued_at: now,
+            updated_at: now,
+            bump,
+        });
+
+        msg!("KYC issued for {}", self.wallet.key());
+
+        Ok(())
+    }
+}
+
+impl<'info> UpdateKyc<'info> {
+    pub fn update_kyc(
+        &mut self,
+        status: KycStatus,
+        jurisdiction: [u8; 2],
+        expires_at: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        validate_terms(jurisdiction, expires_at, now)?;
+
+        let record = &mut self.kyc_record;
+        record.status = status;
+        record.jurisdiction = jurisdiction;
+        record.expires_at = expires_at;
+        record.updated_at = now;
+
+        msg!("KYC updated for {}: {:?}", record.wallet, status);
+
+        Ok(())
+    }
+
+    /// Keeps the record for audit but blocks the wallet from trading and staking.
+    pub fn revoke_kyc(&mut self) -> Result<()> {
+        let record = &mut self.kyc_record;
+        record.status = KycStatus::Revoked;
+        record.updated_at = Clock::get()?.unix_timestamp;
+
+        msg!("KYC revoked for {}", record.wallet);
+
+        Ok(())
+    }
+}
+
+impl<'info> SetComplianceAuthority<'info> {
+    pub fn set_compliance_authority(&mut self, compliance_authority: Pubkey) -> Result<()> {
+        require!(
+            compliance_authority != Pubkey::default(),
+            RwaError::Unauthorized
+        );
+
+        self.protocol_config.compliance_authority = compliance_authority;
+
+        msg!("Compliance authority set to {}", compliance_authority);
+
+        Ok(())
+    }
+}
+
+impl<'info> SetJurisdictionRules<'info> {
+    /// Replaces the city's blocked jurisdictions.
+    pub fn set_jurisdiction_rules(
+        &mut self,
+        city_name: String,
+        blocked: Vec<[u8; 2]>,
+    ) -> Result<()> {
+        require!(
+            blocked.len() <= MAX_BLOCKED_JURISDICTIONS,
+            RwaError::TooManyJurisdictions
+        );
+        for jurisdiction in &blocked {
+            validate_jurisdiction(*jurisdiction)?;
+        }
+
+        let rules = &mut self.jurisdiction_rules;
+        rules.blocked = blocked;
+        rules.updated_at = Clock::get()?.unix_timestamp;
+
+        msg!(
+            "Jurisdiction rules for {}: {} blocked",
+            city_name,
+            rules.blocked.len()
+        );
+
+        Ok(())
+    }
+}
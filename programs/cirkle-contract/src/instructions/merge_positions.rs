@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::constants::FEATURE_STAKING;
+use crate::error::RwaError;
+use crate::state::{ProtocolConfig, StakePool, UserStake};
+use crate::utils::transfer_checked_with_hook;
+
+/// Folds the source position into the target and closes the source.
+#[derive(Accounts)]
+#[instruction(source_id: u64, target_id: u64)]
+pub struct MergePositions<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub city_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_mint.key().as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [
+            b"stake",
+            user.key().as_ref(),
+            city_mint.key().as_ref(),
+            &source_id.to_le_bytes()
+        ],
+        bump = source_stake.bump
+    )]
+    pub source_stake: Account<'info, UserStake>,
+
+    #[account(
+        mut,
+        associated_token::mint = city_mint,
+        associated_token::authority = source_stake,
+        associated_token::token_program = token_program,
+    )]
+    pub source_vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake",
+            user.key().as_ref(),
+            city_mint.key().as_ref(),
+            &target_id.to_le_bytes()
+        ],
+        bump = target_stake.bump
+    )]
+    pub target_stake: Account<'info, UserStake>,
+
+    #[account(
+        mut,
+        associated_token::mint = city_mint,
+        associated_token::authority = target_stake,
+        associated_token::token_program = token_program,
+    )]
+    pub target_vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> MergePositions<'info> {
+    pub fn merge_positions(
+        &mut self,
+        source_id: u64,
+        target_id: u64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        self.protocol_config.require_feature(FEATURE_STAKING)?;
+        require!(source_id != target_id, RwaError::SamePosition);
+        require!(
+            self.source_stake.unbonding_amount == 0,
+            RwaError::UnbondingInProgress
+        );
+
+        let source = &mut self.source_stake;
+        let target = &mut self.target_stake;
+        let stake_pool = &mut self.stake_pool;
+
+        let now = Clock::get()?.unix_timestamp;
+        stake_pool.update(now)?;
+        source.settle(stake_pool, now)?;
+        target.settle(stake_pool, now)?;
+
+        let binding = self.city_mint.key();
+        let position_id = source.position_id.to_le_bytes();
+        let stake_seeds: &[&[u8]] = &[
+            b"stake",
+            self.user.key.as_ref(),
+            binding.as_ref(),
+            &position_id,
+            &[source.bump],
+        ];
+
+        if source.staked_amount > 0 {
+            let cpi_merge = TransferChecked {
+                from: self.source_vault_ata.to_account_info(),
+                mint: self.city_mint.to_account_info(),
+                to: self.target_vault_ata.to_account_info(),
+                authority: source.to_account_info(),
+            };
+
+            transfer_checked_with_hook(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    cpi_merge,
+                    &[stake_seeds],
+                )
+                .with_remaining_accounts(hook_accounts.to_vec()),
+                source.staked_amount,
+                self.city_mint.decimals,
+            )?;
+        }
+
+        let cpi_close = CloseAccount {
+            account: self.source_vault_ata.to_account_info(),
+            destination: self.user.to_account_info(),
+            authority: source.to_account_info(),
+        };
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_close,
+            &[stake_seeds],
+        ))?;
+
+        // The merged position keeps the stricter tier of the two, and the
+        // whole balance is locked for that tier's full term from now, so a
+        // small position cannot lend its boost to a large one. Ended locks
+        // were already dropped by `settle`.
+        if target.staked_amount == 0 {
+            target.stake_start = source.stake_start;
+        } else if source.staked_amount > 0 {
+            target.stake_start = target.stake_start.min(source.stake_start);
+        }
+        target.lock_tier = target.lock_tier.max(source.lock_tier);
+        target.reward_multiplier_bps = target.lock_tier.multiplier_bps();
+        let relock_end = now
+            .checked_add(target.lock_tier.duration())
+            .ok_or(RwaError::Overflow)?;
+        target.lock_end = target.lock_end.max(source.lock_end).max(relock_end);
+
+        target.staked_amount = target
+            .staked_amount
+            .checked_add(source.staked_amount)
+            .ok_or(RwaError::Overflow)?;
+        target.pending_rewards = target
+            .pending_rewards
+            .checked_add(source.pending_rewards)
+            .ok_or(RwaError::Overflow)?;
+        source.staked_amount = 0;
+        source.pending_rewards = 0;
+        source.sync_reward_debt(stake_pool)?;
+        target.sync_reward_debt(stake_pool)?;
+
+        Ok(())
+    }
+}
@@ -10,8 +10,14 @@ pub use withdraw::*;
 pub mod stake;
 pub use stake::*;
 
-pub mod unstake;
-pub use unstake::*;
+pub mod request_unstake;
+pub use request_unstake::*;
+
+pub mod complete_unstake;
+pub use complete_unstake::*;
+
+pub mod cancel_unstake;
+pub use cancel_unstake::*;
 
 pub mod claim;
 pub use claim::*;
@@ -21,3 +27,75 @@ pub use sell::*;
 
 pub mod close_stake;
 pub use close_stake::*;
+
+pub mod configure_oracle;
+pub use configure_oracle::*;
+
+pub mod set_city_price;
+pub use set_city_price::*;
+
+pub mod create_city;
+pub use create_city::*;
+
+pub mod update_protocol_config;
+pub use update_protocol_config::*;
+
+pub mod pause;
+pub use pause::*;
+
+pub mod set_city_status;
+pub use set_city_status::*;
+
+pub mod set_city_fees;
+pub use set_city_fees::*;
+
+pub mod withdraw_fees;
+pub use withdraw_fees::*;
+
+pub mod vault_health;
+pub use vault_health::*;
+
+pub mod set_reward_rate;
+pub use set_reward_rate::*;
+
+pub mod set_early_exit_penalty;
+pub use set_early_exit_penalty::*;
+
+pub mod open_position;
+pub use open_position::*;
+
+pub mod merge_positions;
+pub use merge_positions::*;
+
+pub mod split_position;
+pub use split_position::*;
+
+pub mod fund_rewards;
+pub use fund_rewards::*;
+
+pub mod set_reward_mint;
+pub use set_reward_mint::*;
+
+pub mod compound;
+pub use compound::*;
+
+pub mod set_unbonding_period;
+pub use set_unbonding_period::*;
+
+pub mod stake_at;
+pub use stake_at::*;
+
+pub mod kyc;
+pub use kyc::*;
+
+pub mod freeze;
+pub use freeze::*;
+
+pub mod set_city_metadata;
+pub use set_city_metadata::*;
+
+pub mod sweep_penalties;
+pub use sweep_penalties::*;
+
+pub mod set_reward_price;
+pub use set_reward_price::*;
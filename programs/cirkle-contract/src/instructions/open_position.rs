@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::constants::FEATURE_STAKING;
+use crate::error::RwaError;
+use crate::state::{
+    CityConfig, JurisdictionRules, LockTier, ProtocolConfig, StakeHistory, StakePool, UserStake,
+};
+use crate::utils::{require_kyc, transfer_checked_with_hook};
+
+#[derive(Accounts)]
+#[instruction(position_id: u64)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Caller's KYC record, loaded so a missing one fails with `KycRequired`
+    #[account(
+        seeds = [b"kyc", user.key().as_ref()],
+        bump,
+    )]
+    pub kyc_record: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Only mints issued by the protocol vault can be staked
+    #[account(
+        constraint = city_mint.mint_authority == COption::Some(protocol_config.vault) @ RwaError::InvalidMint,
+    )]
+    pub city_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"jurisdiction-rules", city_mint.key().as_ref()],
+        bump = jurisdiction_rules.bump,
+    )]
+    pub jurisdiction_rules: Account<'info, JurisdictionRules>,
+
+    #[account(
+        constraint = city_config.mint == city_mint.key() @ RwaError::InvalidMint,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_mint.key().as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        constraint = user_city_ata.mint == city_mint.key(),
+        constraint = user_city_ata.owner == user.key(),
+    )]
+    pub user_city_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserStake::INIT_SPACE,
+        seeds = [
+            b"stake",
+            user.key.as_ref(),
+            city_mint.key().as_ref(),
+            &position_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + StakeHistory::INIT_SPACE,
+        seeds = [b"stake-history", user.key().as_ref(), city_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_history: Account<'info, StakeHistory>,
+
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = city_mint,
+        associated_token::authority = user_stake,
+        associated_token::token_program = token_program,
+    )]
+    pub stake_vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenPosition<'info> {
+    pub fn open_position(
+        &mut self,
+        position_id: u64,
+        amount: u64,
+        lock_tier: LockTier,
+        bump: u8,
+        history_bump: u8,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        self.protocol_config.require_feature(FEATURE_STAKING)?;
+        require!(amount > 0, RwaError::InvalidAmount);
+        require!(
+            self.city_config.status.can_stake(),
+            RwaError::CityStakeDisabled
+        );
+
+        let user_stake = &mut self.user_stake;
+        user_stake.owner = self.user.key();
+        user_stake.city_mint = self.city_mint.key();
+        user_stake.vault_ata = self.stake_vault_ata.key();
+        user_stake.bump = bump;
+        user_stake.position_id = position_id;
+
+        let now = Clock::get()?.unix_timestamp;
+        let kyc = require_kyc(&self.kyc_record.to_account_info(), now)?;
+        self.jurisdiction_rules.require_allowed(kyc.jurisdiction)?;
+        self.stake_pool.update(now)?;
+        user_stake.add_stake(&mut self.stake_pool, amount, lock_tier, now)?;
+
+        let stake_history = &mut self.stake_history;
+        if stake_history.owner == Pubkey::default() {
+            stake_history.owner = self.user.key();
+            stake_history.city_mint = self.city_mint.key();
+            stake_history.bump = history_bump;
+        }
+        let slot = Clock::get()?.slot;
+        stake_history.add(amount, slot)?;
+        self.stake_pool.checkpoint(slot);
+
+        let cpi_accounts = TransferChecked {
+            from: self.user_city_ata.to_account_info(),
+            mint: self.city_mint.to_account_info(),
+            to: self.stake_vault_ata.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+
+        let cpi_program = self.token_program.to_account_info();
+
+        transfer_checked_with_hook(
+            CpiContext::new(cpi_program, cpi_accounts)
+                .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            self.city_mint.decimals,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::FEATURES_ALL;
+use crate::error::RwaError;
+use crate::state::ProtocolConfig;
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = guardian @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct Unpause<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+impl<'info> Pause<'info> {
+    pub fn pause(&mut self, flags: u8) -> Result<()> {
+        require!(
+            flags != 0 && flags & !FEATURES_ALL == 0,
+            RwaError::InvalidPauseFlags
+        );
+
+        let config = &mut self.protocol_config;
+        config.paused |= flags;
+
+        msg!("Protocol paused by guardian {}", self.guardian.key());
+        msg!("   Paused: {:#010b}", config.paused);
+
+        Ok(())
+    }
+}
+
+impl<'info> Unpause<'info> {
+    pub fn unpause(&mut self, flags: u8) -> Result<()> {
+        require!(
+            flags != 0 && flags & !FEATURES_ALL == 0,
+            RwaError::InvalidPauseFlags
+        );
+
+        let config = &mut self.protocol_config;
+        config.paused &= !flags;
+
+        msg!("Protocol unpaused by admin {}", self.admin.key());
+        msg!("   Paused: {:#010b}", config.paused);
+
+        Ok(())
+    }
+}
+
+impl<'info> SetGuardian<'info> {
+    pub fn set_guardian(&mut self, guardian: Pubkey) -> Result<()> {
+        require!(guardian != Pubkey::default(), RwaError::InvalidGuardian);
+
+        self.protocol_config.guardian = guardian;
+
+        msg!("Guardian set to {}", guardian);
+
+        Ok(())
+    }
+}
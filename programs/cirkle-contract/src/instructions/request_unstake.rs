@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::RwaError;
+use crate::constants::FEATURE_STAKING;
+use crate::state::{ProtocolConfig, StakeHistory, StakePool, UserStake};
+use crate::utils::{fee_amount, transfer_checked_with_hook};
+
+/// Moves stake into the position's unbonding bucket, where it stops earning
+/// until `complete_unstake` releases it after the city's cooldown.
+#[derive(Accounts)]
+#[instruction(position_id: u64)]
+pub struct RequestUnstake<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub city_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_mint.key().as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake",
+            user.key().as_ref(),
+            city_mint.key().as_ref(),
+            &position_id.to_le_bytes()
+        ],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-history", user.key().as_ref(), city_mint.key().as_ref()],
+        bump = stake_history.bump,
+    )]
+    pub stake_history: Account<'info, StakeHistory>,
+
+    #[account(
+        mut,
+        associated_token::mint = city_mint,
+        associated_token::authority = user_stake,
+        associated_token::token_program = token_program,
+    )]
+    pub stake_vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives early-exit penalties; this is the reward pool when rewards
+    /// are paid in the city token, otherwise they wait for `sweep_penalties`
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = city_mint,
+        associated_token::authority = stake_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub pool_city_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RequestUnstake<'info> {
+    pub fn request_unstake(
+        &mut self,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        self.protocol_config.require_feature(FEATURE_STAKING)?;
+        require!(amount > 0, RwaError::InvalidAmount);
+
+        let user_stake = &mut self.user_stake;
+        let stake_pool = &mut self.stake_pool;
+
+        require!(user_stake.staked_amount > 0, RwaError::NothingStaked);
+        require!(
+            user_stake.staked_amount >= amount,
+            RwaError::InsufficientStakedAmount
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let penalty = if user_stake.is_locked(now) {
+            let penalty_bps = stake_pool
+                .early_exit_penalty_bps
+                .ok_or(RwaError::StakeLocked)?;
+            fee_amount(amount, penalty_bps)?
+        } else {
+            0
+        };
+
+        // Accrued rewards stay pending on the position until claimed
+        stake_pool.update(now)?;
+        user_stake.remove_stake(stake_pool, amount, now)?;
+
+        let slot = Clock::get()?.slot;
+        self.stake_history.sub(amount, slot)?;
+        stake_pool.checkpoint(slot);
+
+        let binding = self.city_mint.key();
+        let position_id = user_stake.position_id.to_le_bytes();
+        let stake_seeds: &[&[u8]] = &[
+            b"stake",
+            self.user.key.as_ref(),
+            binding.as_ref(),
+            &position_id,
+            &[user_stake.bump],
+        ];
+
+        if penalty > 0 {
+            let cpi_penalty = TransferChecked {
+                from: self.stake_vault_ata.to_account_info(),
+                mint: self.city_mint.to_account_info(),
+                to: self.pool_city_ata.to_account_info(),
+                authority: user_stake.to_account_info(),
+            };
+
+            transfer_checked_with_hook(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    cpi_penalty,
+                    &[stake_seeds],
+                )
+                .with_remaining_accounts(hook_accounts.to_vec()),
+                penalty,
+                self.city_mint.decimals,
+            )?;
+
+            // Penalties fund rewards when those are paid in the city token;
+            // otherwise they are held for the admin to sweep
+            if stake_pool.reward_mint == self.city_mint.key() {
+                stake_pool.reward_balance = stake_pool
+                    .reward_balance
+                    .checked_add(penalty)
+                    .ok_or(RwaError::Overflow)?;
+            } else {
+                stake_pool.penalty_balance = stake_pool
+                    .penalty_balance
+                    .checked_add(penalty)
+                    .ok_or(RwaError::Overflow)?;
+            }
+        }
+
+        // A new request restarts the cooldown for the whole bucket
+        user_stake.unbonding_amount = user_stake
+            .unbonding_amount
+            .checked_add(amount - penalty)
+            .ok_or(RwaError::Overflow)?;
+        user_stake.unbonding_end = now
+            .checked_add(stake_pool.unbonding_period)
+            .ok_or(RwaError::Overflow)?;
+
+        Ok(())
+    }
+}
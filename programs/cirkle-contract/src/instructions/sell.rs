@@ -1,7 +1,13 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
-
-use crate::state::CityConfig;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+
+use crate::constants::FEATURE_TRADING;
+use crate::events::TokensSold;
+use crate::state::{CityConfig, CityPrice, CityStatus, FeeTreasury, ProtocolConfig};
+use crate::utils::{
+    check_deadline, fee_amount, pay_from_vault, require_kyc, tokens_to_lamports, vault_available,
+    OraclePrice,
+};
 use crate::{error::RwaError, state::Vault};
 
 #[derive(Accounts)]
@@ -10,16 +16,40 @@ pub struct Sell<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    /// CHECK: Admin that owns the vault PDA
+    /// CHECK: Caller's KYC record, loaded so a missing one fails with `KycRequired`
+    #[account(
+        seeds = [b"kyc", user.key().as_ref()],
+        bump,
+    )]
+    pub kyc_record: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = vault @ RwaError::InvalidVault,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
     #[account(mut)]
-    pub admin: UncheckedAccount<'info>,
+    pub vault: Account<'info, Vault>,
 
     #[account(
         mut,
-        seeds = [b"protocol_admin", admin.key().as_ref()],
-        bump
+        seeds = [b"fee-treasury"],
+        bump = fee_treasury.bump,
     )]
-    pub vault: Account<'info, Vault>,
+    pub fee_treasury: Account<'info, FeeTreasury>,
+
+    /// CHECK: Pyth SOL/USD price account, parsed in `sell_token`
+    #[account(address = protocol_config.sol_usd_feed @ RwaError::InvalidPriceFeed)]
+    pub sol_usd_price: UncheckedAccount<'info>,
+
+    /// Admin-maintained USD price of one city token
+    #[account(
+        seeds = [b"city-price", city_name.as_bytes()],
+        bump = city_price.bump,
+    )]
+    pub city_price: Account<'info, CityPrice>,
 
     /// City configuration - stores metadata about the city's token
     #[account(
@@ -35,17 +65,18 @@ pub struct Sell<'info> {
         seeds = [b"city-mint", city_name.as_bytes()],
         bump,
     )]
-    pub city_mint: Account<'info, Mint>,
+    pub city_mint: InterfaceAccount<'info, Mint>,
 
     /// User's Associated Token Account for this specific city token
     #[account(
         mut,
         associated_token::mint = city_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_ata: Account<'info, TokenAccount>,
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
@@ -54,19 +85,39 @@ impl<'info> Sell<'info> {
         &mut self,
         city_name: String,
         token_amount: u64,
-        circle_rate: u64,
-        sol_price_usd: u64,
-        _vault_bump: u8,
+        min_lamports_out: u64,
+        deadline: Option<i64>,
     ) -> Result<()> {
-        require!(circle_rate > 0, RwaError::RateNotValid);
-        require!(sol_price_usd > 0, RwaError::RateNotValid);
+        self.protocol_config.require_feature(FEATURE_TRADING)?;
         require!(token_amount > 0, RwaError::InvalidAmount);
 
+        let config = &self.protocol_config;
+        let now = Clock::get()?.unix_timestamp;
+        check_deadline(deadline, now)?;
+        require_kyc(&self.kyc_record.to_account_info(), now)?;
+        let sol_price_usd = OraclePrice::from_pyth(&self.sol_usd_price.to_account_info())?
+            .validated(now, config.max_price_age, config.max_confidence_bps)?;
+        // A redeemed city's final valuation can no longer be updated, so it
+        // never goes stale
+        let max_city_price_age = if self.city_config.status == CityStatus::Redeemed {
+            u64::MAX
+        } else {
+            config.max_city_price_age
+        };
+        let circle_rate = self
+            .city_price
+            .oracle_price()
+            .validated(now, max_city_price_age, config.max_confidence_bps)?;
+
         // Verify the city config matches the mint
         require!(
             self.city_config.mint == self.city_mint.key(),
             RwaError::InvalidMint
         );
+        require!(
+            self.city_config.status.can_sell(),
+            RwaError::CitySellDisabled
+        );
 
         msg!("🏙️ Selling city token: {}", city_name);
         msg!("   Mint address: {}", self.city_mint.key());
@@ -76,24 +127,29 @@ impl<'info> Sell<'info> {
         msg!("   Token amount to burn: {}", token_amount);
 
         // Calculate SOL to return (reverse of buy calculation)
-        // Reverse of: tokens = (usd * 1_000_000) / rate
-        // So: usd = tokens * rate / 1_000_000
-        // Then: lamports = (usd / sol_price) * 1_000_000_000
-        // Combined with precision: lamports = (tokens * rate * 1_000_000_000) / (1_000_000 * sol_price)
-
-        let lamports = token_amount
-            .checked_mul(circle_rate)
-            .and_then(|v| v.checked_mul(1_000_000_000))
-            .and_then(|v| v.checked_div(1_000_000))
-            .and_then(|v| v.checked_div(sol_price_usd))
-            .ok_or(RwaError::DivideByZero)?;
+        // lamports = (tokens * rate * 1_000_000_000) / (1_000_000 * sol_price)
+        let lamports = tokens_to_lamports(token_amount, sol_price_usd, circle_rate)?;
 
         require!(lamports > 0, RwaError::InvalidAmount);
 
-        msg!("   Lamports to return: {}", lamports);
+        // The fee is kept out of what the vault pays back to the user
+        let fee_bps = self.city_config.sell_fee_bps(config);
+        let fee_lamports = fee_amount(lamports, fee_bps)?;
+        let user_lamports = lamports - fee_lamports;
+        require!(user_lamports > 0, RwaError::InvalidAmount);
+        require!(
+            user_lamports >= min_lamports_out,
+            RwaError::SlippageExceeded
+        );
+
+        msg!("   Lamports to return: {}", user_lamports);
+        msg!("   Fee: {} lamports ({} bps)", fee_lamports, fee_bps);
 
         // Check vault has sufficient balance
-        require!(self.vault.balance >= lamports, RwaError::InsufficientFunds);
+        require!(
+            vault_available(&self.vault)? >= lamports,
+            RwaError::InsufficientFunds
+        );
 
         // Burn tokens from user's ATA
         let cpi_accounts = Burn {
@@ -102,42 +158,54 @@ impl<'info> Sell<'info> {
             authority: self.user.to_account_info(),
         };
         let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
-        token::burn(cpi_ctx, token_amount)?;
+        burn(cpi_ctx, token_amount)?;
 
         msg!("   Tokens burned successfully!");
 
-        // Update city config total supply
-        self.city_config.total_supply = self
-            .city_config
-            .total_supply
-            .checked_sub(token_amount)
+        // Holders exit against their pro-rata share of the city's liabilities;
+        // price gains beyond it come from reserves
+        let released = self.city_config.retire(token_amount)?;
+        self.vault.liabilities = self
+            .vault
+            .liabilities
+            .checked_sub(released)
             .ok_or(RwaError::Overflow)?;
 
         msg!("   Total city supply: {}", self.city_config.total_supply);
 
-        // Transfer SOL from vault to user by directly manipulating lamports
-        // We can't use system program transfer because vault account has data
-        // Instead, subtract from vault and add to user
-        let vault_info = self.vault.to_account_info();
-        let mut vault_lamps = vault_info.lamports.borrow_mut();
-        **vault_lamps -= lamports;
-        drop(vault_lamps);
-
-        let user_info = self.user.to_account_info();
-        let mut user_lamps = user_info.lamports.borrow_mut();
-        **user_lamps += lamports;
-        drop(user_lamps);
-
-        // Update vault balance
-        self.vault.balance = self
-            .vault
-            .balance
-            .checked_sub(lamports)
-            .ok_or(RwaError::Overflow)?;
+        // Pay the user and route the fee to the treasury
+        pay_from_vault(&mut self.vault, &self.user.to_account_info(), user_lamports)?;
+        if fee_lamports > 0 {
+            pay_from_vault(
+                &mut self.vault,
+                &self.fee_treasury.to_account_info(),
+                fee_lamports,
+            )?;
+
+            let treasury = &mut self.fee_treasury;
+            treasury.balance = treasury
+                .balance
+                .checked_add(fee_lamports)
+                .ok_or(RwaError::Overflow)?;
+            treasury.total_collected = treasury
+                .total_collected
+                .checked_add(fee_lamports)
+                .ok_or(RwaError::Overflow)?;
+        }
 
         msg!("   Vault balance: {} lamports", self.vault.balance);
         msg!("✅ Sell completed successfully!");
 
+        emit!(TokensSold {
+            user: self.user.key(),
+            city_mint: self.city_mint.key(),
+            tokens_burned: token_amount,
+            lamports_returned: user_lamports,
+            fee_lamports,
+            sol_price_usd,
+            city_price_usd: circle_rate,
+        });
+
         Ok(())
     }
 }
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_FEE_BPS;
+use crate::error::RwaError;
+use crate::state::{CityConfig, ProtocolConfig};
+
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct SetCityFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+}
+
+impl<'info> SetCityFees<'info> {
+    pub fn set_city_fees(
+        &mut self,
+        city_name: String,
+        buy_fee_bps: Option<u16>,
+        sell_fee_bps: Option<u16>,
+    ) -> Result<()> {
+        require!(
+            buy_fee_bps.unwrap_or(0) <= MAX_FEE_BPS && sell_fee_bps.unwrap_or(0) <= MAX_FEE_BPS,
+            RwaError::FeeTooHigh
+        );
+
+        let city_config = &mut self.city_config;
+        city_config.buy_fee_bps = buy_fee_bps;
+        city_config.sell_fee_bps = sell_fee_bps;
+
+        msg!("City fees updated: {}", city_name);
+        msg!(
+            "   Buy fee: {} bps, sell fee: {} bps",
+            city_config.buy_fee_bps(&self.protocol_config),
+            city_config.sell_fee_bps(&self.protocol_config)
+        );
+
+        Ok(())
+    }
+}
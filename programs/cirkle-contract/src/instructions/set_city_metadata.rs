@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{token_metadata_update_field, Mint, TokenMetadataUpdateField},
+};
+
+use crate::error::RwaError;
+use crate::events::CityMetadataUpdated;
+use crate::state::{CityConfig, CityMetadataField, ProtocolConfig, Vault};
+use crate::utils::top_up_rent;
+
+/// Accounts for writing a city's in-mint metadata, shared by the
+/// single-field and full updates.
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct SetCityMetadata<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+        has_one = vault @ RwaError::InvalidVault,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Update authority of the in-mint metadata
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(
+        mut,
+        address = city_config.mint @ RwaError::InvalidMint,
+        mint::token_program = token_program,
+    )]
+    pub city_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetCityMetadata<'info> {
+    pub fn set_city_metadata_field(
+        &mut self,
+        city_name: String,
+        field: CityMetadataField,
+        value: String,
+    ) -> Result<()> {
+        self.update_field(field, value.clone())?;
+        self.top_up_mint_rent()?;
+
+        msg!("City {} metadata {:?} set to {}", city_name, field, value);
+
+        Ok(())
+    }
+
+    /// Replaces the city's name, symbol and URI in one go, e.g. to publish a
+    /// refreshed appraisal document.
+    pub fn update_city_metadata(
+        &mut self,
+        city_name: String,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        self.update_field(CityMetadataField::Name, name.clone())?;
+        self.update_field(CityMetadataField::Symbol, symbol.clone())?;
+        self.update_field(CityMetadataField::Uri, uri.clone())?;
+        self.top_up_mint_rent()?;
+
+        msg!("City {} metadata updated", city_name);
+
+        emit!(CityMetadataUpdated {
+            city_mint: self.city_mint.key(),
+            name,
+            symbol,
+            uri,
+        });
+
+        Ok(())
+    }
+
+    /// Writes one field of the in-mint metadata, signed by the vault, and
+    /// mirrors the URI into `CityConfig`.
+    fn update_field(&mut self, field: CityMetadataField, value: String) -> Result<()> {
+        require!(
+            value.len() <= field.max_len(),
+            RwaError::InvalidMetadataValue
+        );
+
+        let signer_seeds = self.vault.signer_seeds();
+        let signer = &[&signer_seeds[..]];
+
+        let cpi_accounts = TokenMetadataUpdateField {
+            program_id: self.token_program.to_account_info(),
+            metadata: self.city_mint.to_account_info(),
+            update_authority: self.vault.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer);
+
+        if field == CityMetadataField::Uri {
+            self.city_config.metadata_uri = value.clone();
+        }
+
+        token_metadata_update_field(cpi_ctx, field.to_field(), value)
+    }
+
+    /// A longer value grows the mint, which must stay rent exempt.
+    fn top_up_mint_rent(&self) -> Result<()> {
+        top_up_rent(
+            &self.city_mint.to_account_info(),
+            &self.admin.to_account_info(),
+            &self.system_program.to_account_info(),
+        )
+    }
+}
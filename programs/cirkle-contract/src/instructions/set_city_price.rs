@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+use crate::state::{CityConfig, CityPrice, CityStatus, ProtocolConfig};
+
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct SetCityPrice<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [b"city-price", city_name.as_bytes()],
+        bump,
+        space = 8 + CityPrice::INIT_SPACE,
+    )]
+    pub city_price: Account<'info, CityPrice>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetCityPrice<'info> {
+    pub fn set_city_price(
+        &mut self,
+        city_name: String,
+        price: u64,
+        conf: u64,
+        bump: u8,
+    ) -> Result<()> {
+        require!(price > 0, RwaError::InvalidPrice);
+        // The final valuation is locked once a city is redeemed
+        require!(
+            self.city_config.status != CityStatus::Redeemed,
+            RwaError::InvalidStatusTransition
+        );
+
+        let city_price = &mut self.city_price;
+        city_price.price = price;
+        city_price.conf = conf;
+        city_price.last_updated = Clock::get()?.unix_timestamp;
+        city_price.bump = bump;
+
+        msg!("City price updated: {}", city_name);
+        msg!("   Price: {} (conf {})", price, conf);
+
+        Ok(())
+    }
+}
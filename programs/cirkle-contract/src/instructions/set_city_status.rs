@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+use crate::state::{CityConfig, CityStatus, ProtocolConfig};
+
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct SetCityStatus<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+}
+
+impl<'info> SetCityStatus<'info> {
+    pub fn set_city_status(&mut self, city_name: String, status: CityStatus) -> Result<()> {
+        let city_config = &mut self.city_config;
+
+        require!(
+            city_config.status.can_transition_to(status),
+            RwaError::InvalidStatusTransition
+        );
+
+        msg!(
+            "City {} status: {:?} -> {:?}",
+            city_name,
+            city_config.status,
+            status
+        );
+        city_config.status = status;
+
+        Ok(())
+    }
+}
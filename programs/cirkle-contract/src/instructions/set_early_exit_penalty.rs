@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_EARLY_EXIT_PENALTY_BPS;
+use crate::error::RwaError;
+use crate::state::{CityConfig, ProtocolConfig, StakePool};
+
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct SetEarlyExitPenalty<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_config.mint.as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
+
+impl<'info> SetEarlyExitPenalty<'info> {
+    pub fn set_early_exit_penalty(
+        &mut self,
+        city_name: String,
+        penalty_bps: Option<u16>,
+    ) -> Result<()> {
+        require!(
+            penalty_bps.unwrap_or(0) <= MAX_EARLY_EXIT_PENALTY_BPS,
+            RwaError::EarlyExitPenaltyTooHigh
+        );
+
+        self.stake_pool.early_exit_penalty_bps = penalty_bps;
+
+        msg!("Early exit penalty updated: {}", city_name);
+        match penalty_bps {
+            Some(bps) => msg!("   Penalty: {} bps", bps),
+            None => msg!("   Early exits rejected"),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::error::RwaError;
+use crate::state::{CityConfig, ProtocolConfig, StakePool};
+
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct SetRewardMint<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_config.mint.as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+}
+
+impl<'info> SetRewardMint<'info> {
+    pub fn set_reward_mint(&mut self, city_name: String) -> Result<()> {
+        // Switching mints with a funded pool would strand the old tokens
+        require!(
+            self.stake_pool.reward_balance == 0,
+            RwaError::RewardPoolNotEmpty
+        );
+        // Rewards are not converted between mints, so nothing earned under
+        // the old mint may still be owed or accruing
+        require!(
+            self.stake_pool.total_weight == 0 && self.stake_pool.unclaimed_rewards == 0,
+            RwaError::RewardsOutstanding
+        );
+
+        require!(
+            self.stake_pool.reward_rate_bps == 0
+                || self.reward_mint.key() == self.stake_pool.city_mint,
+            RwaError::RewardRateRequiresCityMint
+        );
+
+        self.stake_pool.reward_mint = self.reward_mint.key();
+        // A price set for the old mint does not carry over
+        self.stake_pool.reward_price_usd = 0;
+
+        msg!("Reward mint updated: {}", city_name);
+        msg!("   Mint: {}", self.reward_mint.key());
+
+        Ok(())
+    }
+}
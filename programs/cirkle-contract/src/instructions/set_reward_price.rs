@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+use crate::state::{CityConfig, ProtocolConfig, StakePool};
+
+/// Prices the pool's reward mint for `compound` when it is neither the city
+/// token nor wrapped SOL, e.g. 1.00 for a USD stablecoin.
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct SetRewardPrice<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_config.mint.as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
+
+impl<'info> SetRewardPrice<'info> {
+    pub fn set_reward_price(&mut self, city_name: String, price_usd: u64) -> Result<()> {
+        require!(price_usd > 0, RwaError::InvalidPrice);
+
+        self.stake_pool.reward_price_usd = price_usd;
+
+        msg!("Reward price updated: {}", city_name);
+        msg!("   Price: {}", price_usd);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_REWARD_APR_BPS, REWARD_RATE_CHANGE_COOLDOWN};
+use crate::error::RwaError;
+use crate::state::{CityConfig, ProtocolConfig, StakePool};
+
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct SetRewardRate<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_config.mint.as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
+
+impl<'info> SetRewardRate<'info> {
+    pub fn set_reward_rate(&mut self, city_name: String, reward_rate_bps: u16) -> Result<()> {
+        require!(
+            reward_rate_bps <= MAX_REWARD_APR_BPS,
+            RwaError::RewardRateTooHigh
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let stake_pool = &mut self.stake_pool;
+        // The rate is paid in reward base units per staked base unit, which
+        // is only a percentage of value when both are the city token
+        require!(
+            reward_rate_bps == 0 || stake_pool.reward_mint == stake_pool.city_mint,
+            RwaError::RewardRateRequiresCityMint
+        );
+        require!(
+            now >= stake_pool.last_rate_change.saturating_add(REWARD_RATE_CHANGE_COOLDOWN),
+            RwaError::RewardRateChangeTooSoon
+        );
+
+        // Close out the old rate before switching
+        stake_pool.update(now)?;
+        stake_pool.reward_rate_bps = reward_rate_bps;
+        stake_pool.last_rate_change = now;
+
+        msg!("Reward rate updated: {}", city_name);
+        msg!("   Rate: {} bps", reward_rate_bps);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_UNBONDING_PERIOD;
+use crate::error::RwaError;
+use crate::state::{CityConfig, ProtocolConfig, StakePool};
+
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct SetUnbondingPeriod<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_config.mint.as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+}
+
+impl<'info> SetUnbondingPeriod<'info> {
+    /// Applies to new unstake requests; pending ones keep their end time.
+    pub fn set_unbonding_period(&mut self, city_name: String, unbonding_period: i64) -> Result<()> {
+        require!(
+            (0..=MAX_UNBONDING_PERIOD).contains(&unbonding_period),
+            RwaError::InvalidUnbondingPeriod
+        );
+
+        self.stake_pool.unbonding_period = unbonding_period;
+
+        msg!("Unbonding period updated: {}", city_name);
+        msg!("   Period: {} seconds", unbonding_period);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::constants::FEATURE_STAKING;
+use crate::error::RwaError;
+use crate::state::{ProtocolConfig, StakePool, UserStake};
+use crate::utils::transfer_checked_with_hook;
+
+/// Moves part of a position into a new one with the same lock terms.
+#[derive(Accounts)]
+#[instruction(source_id: u64, new_id: u64)]
+pub struct SplitPosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub city_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_mint.key().as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"stake",
+            user.key().as_ref(),
+            city_mint.key().as_ref(),
+            &source_id.to_le_bytes()
+        ],
+        bump = source_stake.bump
+    )]
+    pub source_stake: Account<'info, UserStake>,
+
+    #[account(
+        mut,
+        associated_token::mint = city_mint,
+        associated_token::authority = source_stake,
+        associated_token::token_program = token_program,
+    )]
+    pub source_vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserStake::INIT_SPACE,
+        seeds = [
+            b"stake",
+            user.key().as_ref(),
+            city_mint.key().as_ref(),
+            &new_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub new_stake: Account<'info, UserStake>,
+
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = city_mint,
+        associated_token::authority = new_stake,
+        associated_token::token_program = token_program,
+    )]
+    pub new_vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SplitPosition<'info> {
+    pub fn split_position(
+        &mut self,
+        new_id: u64,
+        amount: u64,
+        bump: u8,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        self.protocol_config.require_feature(FEATURE_STAKING)?;
+        require!(amount > 0, RwaError::InvalidAmount);
+
+        let source = &mut self.source_stake;
+        let stake_pool = &mut self.stake_pool;
+
+        // Splitting off the whole balance would leave an empty position
+        require!(
+            source.staked_amount > amount,
+            RwaError::InsufficientStakedAmount
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        stake_pool.update(now)?;
+        source.settle(stake_pool, now)?;
+        source.staked_amount -= amount;
+        source.sync_reward_debt(stake_pool)?;
+
+        self.new_stake.set_inner(UserStake {
+            owner: self.user.key(),
+            city_mint: self.city_mint.key(),
+            staked_amount: amount,
+            stake_start: source.stake_start,
+            vault_ata: self.new_vault_ata.key(),
+            bump,
+            reward_debt: 0,
+            yield_debt: 0,
+            pending_rewards: 0,
+            lock_tier: source.lock_tier,
+            lock_end: source.lock_end,
+            reward_multiplier_bps: source.reward_multiplier_bps,
+            position_id: new_id,
+            weight: 0,
+            unbonding_amount: 0,
+            unbonding_end: 0,
+        });
+        self.new_stake.sync_reward_debt(stake_pool)?;
+
+        let binding = self.city_mint.key();
+        let position_id = source.position_id.to_le_bytes();
+        let stake_seeds: &[&[u8]] = &[
+            b"stake",
+            self.user.key.as_ref(),
+            binding.as_ref(),
+            &position_id,
+            &[source.bump],
+        ];
+
+        let cpi_split = TransferChecked {
+            from: self.source_vault_ata.to_account_info(),
+            mint: self.city_mint.to_account_info(),
+            to: self.new_vault_ata.to_account_info(),
+            authority: source.to_account_info(),
+        };
+
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_split,
+                &[stake_seeds],
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            self.city_mint.decimals,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::error::RwaError;
+use crate::state::{StakeHistory, StakePool};
+use crate::utils::load_account;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct StakeSnapshot {
+    pub staked_amount: u64,
+    pub total_staked: u64,
+}
+
+/// Read-only: returns a user's stake and the city total at the end of `slot`
+/// as return data.
+#[derive(Accounts)]
+pub struct StakeAt<'info> {
+    /// CHECK: only used to derive the history address
+    pub user: UncheckedAccount<'info>,
+
+    pub city_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"stake-pool", city_mint.key().as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    /// CHECK: User's history; a wallet that never staked has none and
+    /// reports zero
+    #[account(
+        seeds = [b"stake-history", user.key().as_ref(), city_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_history: UncheckedAccount<'info>,
+}
+
+impl<'info> StakeAt<'info> {
+    pub fn stake_at(&self, slot: u64) -> Result<StakeSnapshot> {
+        let staked_amount = if self.stake_history.data_is_empty() {
+            0
+        } else {
+            let history: StakeHistory = load_account(
+                &self.stake_history.to_account_info(),
+                RwaError::CheckpointUnavailable,
+            )?;
+            history.staked_at(slot)?
+        };
+        let snapshot = StakeSnapshot {
+            staked_amount,
+            total_staked: self.stake_pool.total_staked_at(slot)?,
+        };
+
+        msg!("Stake at slot {}:", slot);
+        msg!("   User: {}", snapshot.staked_amount);
+        msg!("   Total: {}", snapshot.total_staked);
+
+        Ok(snapshot)
+    }
+}
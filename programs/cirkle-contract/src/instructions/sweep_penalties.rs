@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::RwaError;
+use crate::state::{CityConfig, ProtocolConfig, StakePool};
+use crate::utils::transfer_checked_with_hook;
+
+/// Withdraws early-exit penalties that could not go back to the reward pool
+/// because rewards are paid in a different mint.
+#[derive(Accounts)]
+#[instruction(city_name: String)]
+pub struct SweepPenalties<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"city-config", city_name.as_bytes()],
+        bump = city_config.bump,
+    )]
+    pub city_config: Account<'info, CityConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake-pool", city_config.mint.as_ref()],
+        bump = stake_pool.bump,
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(address = city_config.mint @ RwaError::InvalidMint)]
+    pub city_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = city_mint,
+        associated_token::authority = stake_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub pool_city_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = admin_city_ata.mint == city_mint.key() @ RwaError::InvalidMint,
+        constraint = admin_city_ata.owner == admin.key(),
+    )]
+    pub admin_city_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> SweepPenalties<'info> {
+    pub fn sweep_penalties(
+        &mut self,
+        city_name: String,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(amount > 0, RwaError::InvalidAmount);
+        require!(
+            self.stake_pool.penalty_balance >= amount,
+            RwaError::InsufficientPenaltyBalance
+        );
+
+        let cpi_accounts = TransferChecked {
+            from: self.pool_city_ata.to_account_info(),
+            mint: self.city_mint.to_account_info(),
+            to: self.admin_city_ata.to_account_info(),
+            authority: self.stake_pool.to_account_info(),
+        };
+
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts,
+                &[&self.stake_pool.signer_seeds()],
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            self.city_mint.decimals,
+        )?;
+
+        self.stake_pool.penalty_balance -= amount;
+
+        msg!("Penalties swept: {}", city_name);
+        msg!(
+            "   Amount: {}, remaining: {}",
+            amount,
+            self.stake_pool.penalty_balance
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{
+    FEATURES_ALL, MAX_FEE_BPS, MIN_RESERVE_RATIO_CEILING_BPS, MIN_RESERVE_RATIO_FLOOR_BPS,
+};
+use crate::error::RwaError;
+use crate::state::ProtocolConfig;
+
+#[derive(Accounts)]
+pub struct UpdateProtocolConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+impl<'info> UpdateProtocolConfig<'info> {
+    pub fn update_protocol_config(
+        &mut self,
+        buy_fee_bps: u16,
+        sell_fee_bps: u16,
+        min_reserve_ratio_bps: u16,
+        features: u8,
+    ) -> Result<()> {
+        require!(
+            buy_fee_bps <= MAX_FEE_BPS && sell_fee_bps <= MAX_FEE_BPS,
+            RwaError::FeeTooHigh
+        );
+        require!(
+            (MIN_RESERVE_RATIO_FLOOR_BPS..=MIN_RESERVE_RATIO_CEILING_BPS)
+                .contains(&min_reserve_ratio_bps),
+            RwaError::ReserveRatioOutOfRange
+        );
+        require!(features & !FEATURES_ALL == 0, RwaError::InvalidPauseFlags);
+
+        let config = &mut self.protocol_config;
+        config.buy_fee_bps = buy_fee_bps;
+        config.sell_fee_bps = sell_fee_bps;
+        config.min_reserve_ratio_bps = min_reserve_ratio_bps;
+        config.features = features;
+
+        msg!("Protocol config updated");
+        msg!("   Buy fee: {} bps, sell fee: {} bps", buy_fee_bps, sell_fee_bps);
+        msg!("   Min reserve ratio: {} bps", min_reserve_ratio_bps);
+        msg!("   Features: {:#010b}", features);
+
+        Ok(())
+    }
+}
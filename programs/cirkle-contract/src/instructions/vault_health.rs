@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+use crate::state::{ProtocolConfig, Vault};
+use crate::utils::vault_available;
+
+/// Read-only: returns the vault reserve ratio in basis points as return data.
+#[derive(Accounts)]
+pub struct VaultHealth<'info> {
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = vault @ RwaError::InvalidVault,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub vault: Account<'info, Vault>,
+}
+
+impl<'info> VaultHealth<'info> {
+    pub fn vault_health(&self) -> Result<u64> {
+        let vault = &self.vault;
+        let ratio = vault.reserve_ratio_bps();
+
+        msg!("Vault health:");
+        msg!("   Balance: {} lamports", vault.balance);
+        msg!("   Available: {} lamports", vault_available(vault)?);
+        msg!("   Liabilities: {} lamports", vault.liabilities);
+        msg!("   Reserve ratio: {} bps", ratio);
+        msg!(
+            "   Min reserve ratio: {} bps",
+            self.protocol_config.min_reserve_ratio_bps
+        );
+
+        Ok(ratio)
+    }
+}
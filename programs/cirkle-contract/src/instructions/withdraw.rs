@@ -1,5 +1,8 @@
-use anchor_lang::{prelude::*, solana_program::{program::invoke_signed, system_instruction::transfer}};
-use crate::state::Vault;
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+use crate::state::{ProtocolConfig, Vault};
+use crate::utils::pay_from_vault_reserved;
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
@@ -7,48 +10,34 @@ pub struct Withdraw<'info> {
     pub admin: Signer<'info>,
 
     #[account(
-        mut,
-        seeds = [b"protocol_admin", admin.key().as_ref()],
-        bump = vault.bump,
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+        has_one = vault @ RwaError::InvalidVault,
     )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(mut)]
     pub vault: Account<'info, Vault>,
 
     /// CHECK: receiver of the SOL
     #[account(mut)]
     pub recipient: SystemAccount<'info>,
-
-    pub system_program: Program<'info, System>,
 }
 
 impl<'info> Withdraw<'info> {
     pub fn withdraw(&mut self, amount: u64) -> Result<()> {
-        require!(amount > 0, crate::error::RwaError::AmountNotValid);
-        require!(self.vault.balance >= amount, crate::error::RwaError::InsufficientFunds);
-
-        let seeds = &[
-            b"protocol_admin",
-            self.admin.key.as_ref(),
-            &[self.vault.bump],
-        ];
-        let signer = &[&seeds[..]];
-
-        let ix = transfer(
-            &self.vault.key(),
-            &self.recipient.key(),
-            amount,
-        );
+        require!(amount > 0, RwaError::AmountNotValid);
 
-        invoke_signed(
-            &ix,
-            &[self.vault.to_account_info(), self.recipient.to_account_info()],
-            signer,
+        pay_from_vault_reserved(
+            &mut self.vault,
+            &self.recipient.to_account_info(),
+            amount,
+            self.protocol_config.min_reserve_ratio_bps,
         )?;
 
-        self.vault.balance = self
-            .vault
-            .balance
-            .checked_sub(amount)
-            .ok_or(crate::error::RwaError::Overflow)?;
+        msg!("Withdrew {} lamports to {}", amount, self.recipient.key());
+        msg!("   Vault balance: {} lamports", self.vault.balance);
 
         Ok(())
     }
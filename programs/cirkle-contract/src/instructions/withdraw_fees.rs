@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+use crate::events::FeesWithdrawn;
+use crate::state::{FeeTreasury, ProtocolConfig};
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol-config"],
+        bump = protocol_config.bump,
+        has_one = admin @ RwaError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"fee-treasury"],
+        bump = fee_treasury.bump,
+    )]
+    pub fee_treasury: Account<'info, FeeTreasury>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+}
+
+impl<'info> WithdrawFees<'info> {
+    pub fn withdraw_fees(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, RwaError::AmountNotValid);
+        require!(
+            self.fee_treasury.balance >= amount,
+            RwaError::InsufficientFunds
+        );
+
+        // The treasury is program-owned, so lamports are moved directly
+        let treasury_info = self.fee_treasury.to_account_info();
+        let recipient_info = self.recipient.to_account_info();
+        **treasury_info.try_borrow_mut_lamports()? = treasury_info
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(RwaError::InsufficientFunds)?;
+        **recipient_info.try_borrow_mut_lamports()? = recipient_info
+            .lamports()
+            .checked_add(amount)
+            .ok_or(RwaError::Overflow)?;
+
+        self.fee_treasury.balance -= amount;
+
+        msg!(
+            "Fees withdrawn: {} lamports to {}",
+            amount,
+            self.recipient.key()
+        );
+        msg!(
+            "   Fee treasury balance: {} lamports",
+            self.fee_treasury.balance
+        );
+
+        emit!(FeesWithdrawn {
+            recipient: self.recipient.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
@@ -3,18 +3,110 @@
 use anchor_lang::prelude::*;
 
 declare_id!("4rGhCxGhfqRnmsNzwe9e9VUE6nFvfXuxmep6BUhbcrne");
-mod error;
+mod constants;
+pub mod error;
+mod events;
 mod instructions;
-mod state;
+pub mod state;
+pub mod utils;
 
 use instructions::*;
+use state::{CityMetadataField, CityStatus, KycStatus, LockTier};
 #[program]
 pub mod cirkle_contract {
     use super::*;
 
     pub fn vault_initialize(ctx: Context<AdminVault>) -> Result<()> {
         let bump = ctx.bumps.admin_vault;
-        ctx.accounts.create_vault(bump)?;
+        let config_bump = ctx.bumps.protocol_config;
+        let treasury_bump = ctx.bumps.fee_treasury;
+        ctx.accounts
+            .create_vault(bump, config_bump, treasury_bump)?;
+        Ok(())
+    }
+
+    pub fn create_city(
+        ctx: Context<CreateCity>,
+        city_name: String,
+        metadata_uri: String,
+    ) -> Result<()> {
+        let bump = ctx.bumps.city_config;
+        let pool_bump = ctx.bumps.stake_pool;
+        let rules_bump = ctx.bumps.jurisdiction_rules;
+        ctx.accounts
+            .create_city(city_name, metadata_uri, bump, pool_bump, rules_bump)?;
+        Ok(())
+    }
+
+    pub fn set_city_status(
+        ctx: Context<SetCityStatus>,
+        city_name: String,
+        status: CityStatus,
+    ) -> Result<()> {
+        ctx.accounts.set_city_status(city_name, status)?;
+        Ok(())
+    }
+
+    pub fn set_city_metadata_field(
+        ctx: Context<SetCityMetadata>,
+        city_name: String,
+        field: CityMetadataField,
+        value: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_city_metadata_field(city_name, field, value)?;
+        Ok(())
+    }
+
+    pub fn update_city_metadata(
+        ctx: Context<SetCityMetadata>,
+        city_name: String,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .update_city_metadata(city_name, name, symbol, uri)?;
+        Ok(())
+    }
+
+    pub fn set_city_fees(
+        ctx: Context<SetCityFees>,
+        city_name: String,
+        buy_fee_bps: Option<u16>,
+        sell_fee_bps: Option<u16>,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_city_fees(city_name, buy_fee_bps, sell_fee_bps)?;
+        Ok(())
+    }
+
+    pub fn set_reward_rate(
+        ctx: Context<SetRewardRate>,
+        city_name: String,
+        reward_rate_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.set_reward_rate(city_name, reward_rate_bps)?;
+        Ok(())
+    }
+
+    pub fn set_unbonding_period(
+        ctx: Context<SetUnbondingPeriod>,
+        city_name: String,
+        unbonding_period: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_unbonding_period(city_name, unbonding_period)?;
+        Ok(())
+    }
+
+    pub fn set_early_exit_penalty(
+        ctx: Context<SetEarlyExitPenalty>,
+        city_name: String,
+        penalty_bps: Option<u16>,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_early_exit_penalty(city_name, penalty_bps)?;
         Ok(())
     }
 
@@ -22,65 +114,283 @@ pub mod cirkle_contract {
         ctx: Context<Buy>,
         city_name: String,
         sol_amount: u64,
-        circle_rate: u64,
-        sol_price_usd: u64,
-        metadata_uri: String,
+        min_tokens_out: u64,
+        deadline: Option<i64>,
     ) -> Result<()> {
-        let vault_bump = ctx.bumps.vault;
-        ctx.accounts.buy_token(
-            city_name,
-            sol_amount,
-            circle_rate,
-            sol_price_usd,
-            vault_bump,
-            metadata_uri,
-        )?;
+        ctx.accounts
+            .buy_token(city_name, sol_amount, min_tokens_out, deadline)?;
+        Ok(())
+    }
+    pub fn buy_exact_out(
+        ctx: Context<Buy>,
+        city_name: String,
+        token_amount: u64,
+        max_lamports_in: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts
+            .buy_exact_out(city_name, token_amount, max_lamports_in, deadline)?;
         Ok(())
     }
     pub fn sell(
         ctx: Context<Sell>,
         city_name: String,
-        circle_rate: u64,
-        sol_price_usd: u64,
         token_amount: u64,
+        min_lamports_out: u64,
+        deadline: Option<i64>,
     ) -> Result<()> {
-        let vault_bump = ctx.bumps.vault;
-        ctx.accounts.sell_token(
-            city_name,
-            token_amount,
-            circle_rate,
-            sol_price_usd,
-            vault_bump,
+        ctx.accounts
+            .sell_token(city_name, token_amount, min_lamports_out, deadline)?;
+        Ok(())
+    }
+    pub fn configure_oracle(
+        ctx: Context<ConfigureOracle>,
+        sol_usd_feed: Pubkey,
+        max_price_age: u64,
+        max_confidence_bps: u16,
+        max_city_price_age: u64,
+    ) -> Result<()> {
+        ctx.accounts.configure_oracle(
+            sol_usd_feed,
+            max_price_age,
+            max_confidence_bps,
+            max_city_price_age,
+        )?;
+        Ok(())
+    }
+    pub fn update_protocol_config(
+        ctx: Context<UpdateProtocolConfig>,
+        buy_fee_bps: u16,
+        sell_fee_bps: u16,
+        min_reserve_ratio_bps: u16,
+        features: u8,
+    ) -> Result<()> {
+        ctx.accounts.update_protocol_config(
+            buy_fee_bps,
+            sell_fee_bps,
+            min_reserve_ratio_bps,
+            features,
         )?;
         Ok(())
     }
+    pub fn pause(ctx: Context<Pause>, flags: u8) -> Result<()> {
+        ctx.accounts.pause(flags)?;
+        Ok(())
+    }
+    pub fn unpause(ctx: Context<Unpause>, flags: u8) -> Result<()> {
+        ctx.accounts.unpause(flags)?;
+        Ok(())
+    }
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        ctx.accounts.set_guardian(guardian)?;
+        Ok(())
+    }
+    pub fn set_compliance_authority(
+        ctx: Context<SetComplianceAuthority>,
+        compliance_authority: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_compliance_authority(compliance_authority)?;
+        Ok(())
+    }
+
+    pub fn issue_kyc(ctx: Context<IssueKyc>, jurisdiction: [u8; 2], expires_at: i64) -> Result<()> {
+        let bump = ctx.bumps.kyc_record;
+        ctx.accounts.issue_kyc(jurisdiction, expires_at, bump)?;
+        Ok(())
+    }
+
+    pub fn update_kyc(
+        ctx: Context<UpdateKyc>,
+        status: KycStatus,
+        jurisdiction: [u8; 2],
+        expires_at: i64,
+    ) -> Result<()> {
+        ctx.accounts.update_kyc(status, jurisdiction, expires_at)?;
+        Ok(())
+    }
+
+    pub fn revoke_kyc(ctx: Context<UpdateKyc>) -> Result<()> {
+        ctx.accounts.revoke_kyc()?;
+        Ok(())
+    }
+
+    pub fn set_jurisdiction_rules(
+        ctx: Context<SetJurisdictionRules>,
+        city_name: String,
+        blocked: Vec<[u8; 2]>,
+    ) -> Result<()> {
+        ctx.accounts.set_jurisdiction_rules(city_name, blocked)?;
+        Ok(())
+    }
+
+    pub fn freeze_holder(
+        ctx: Context<SetHolderFrozen>,
+        _city_name: String,
+        reason_code: u16,
+    ) -> Result<()> {
+        let log_bump = ctx.bumps.freeze_log;
+        let bump = ctx.bumps.freeze_record;
+        ctx.accounts.set_holder_frozen(true, reason_code, log_bump, bump)?;
+        Ok(())
+    }
+
+    pub fn thaw_holder(
+        ctx: Context<SetHolderFrozen>,
+        _city_name: String,
+        reason_code: u16,
+    ) -> Result<()> {
+        let log_bump = ctx.bumps.freeze_log;
+        let bump = ctx.bumps.freeze_record;
+        ctx.accounts.set_holder_frozen(false, reason_code, log_bump, bump)?;
+        Ok(())
+    }
+
+    pub fn set_city_price(
+        ctx: Context<SetCityPrice>,
+        city_name: String,
+        price: u64,
+        conf: u64,
+    ) -> Result<()> {
+        let bump = ctx.bumps.city_price;
+        ctx.accounts
+            .set_city_price(city_name, price, conf, bump)?;
+        Ok(())
+    }
     pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         ctx.accounts.withdraw(amount)?;
         Ok(())
     }
-    pub fn stake(ctx: Context<StakeCity>, amount: u64) -> Result<()> {
+    pub fn vault_health(ctx: Context<VaultHealth>) -> Result<u64> {
+        ctx.accounts.vault_health()
+    }
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        ctx.accounts.withdraw_fees(amount)?;
+        Ok(())
+    }
+    pub fn open_position<'info>(
+        ctx: Context<'_, '_, '_, 'info, OpenPosition<'info>>,
+        position_id: u64,
+        amount: u64,
+        lock_tier: LockTier,
+    ) -> Result<()> {
         let bump = ctx.bumps.user_stake;
-        ctx.accounts.user_stake.bump = bump;
-        ctx.accounts.stake_city(amount)?;
+        let history_bump = ctx.bumps.stake_history;
+        ctx.accounts.open_position(
+            position_id,
+            amount,
+            lock_tier,
+            bump,
+            history_bump,
+            ctx.remaining_accounts,
+        )?;
         Ok(())
     }
-    pub fn unstake(
-        ctx: Context<UnstakeCity>,
+    pub fn stake<'info>(
+        ctx: Context<'_, '_, '_, 'info, StakeCity<'info>>,
+        _position_id: u64,
         amount: u64,
-        city_price_usd: u64,
-        sol_price_usd: u64,
+        lock_tier: LockTier,
     ) -> Result<()> {
-        ctx.accounts
-            .unstake_city(amount, city_price_usd, sol_price_usd)?;
+        ctx.accounts.stake_city(amount, lock_tier, ctx.remaining_accounts)?;
         Ok(())
     }
-    pub fn claim(ctx: Context<ClaimReward>, city_price_usd: u64, sol_price_usd: u64) -> Result<()> {
+    pub fn merge_positions<'info>(
+        ctx: Context<'_, '_, '_, 'info, MergePositions<'info>>,
+        source_id: u64,
+        target_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.merge_positions(source_id, target_id, ctx.remaining_accounts)?;
+        Ok(())
+    }
+    pub fn split_position<'info>(
+        ctx: Context<'_, '_, '_, 'info, SplitPosition<'info>>,
+        _source_id: u64,
+        new_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        let bump = ctx.bumps.new_stake;
+        ctx.accounts.split_position(new_id, amount, bump, ctx.remaining_accounts)?;
+        Ok(())
+    }
+    pub fn request_unstake<'info>(
+        ctx: Context<'_, '_, '_, 'info, RequestUnstake<'info>>,
+        _position_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.request_unstake(amount, ctx.remaining_accounts)?;
+        Ok(())
+    }
+    pub fn complete_unstake<'info>(
+        ctx: Context<'_, '_, '_, 'info, CompleteUnstake<'info>>,
+        _position_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.complete_unstake(ctx.remaining_accounts)?;
+        Ok(())
+    }
+    pub fn cancel_unstake(ctx: Context<CancelUnstake>, _position_id: u64) -> Result<()> {
+        ctx.accounts.cancel_unstake()?;
+        Ok(())
+    }
+    pub fn claim<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimReward<'info>>,
+        _position_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.claim_reward(ctx.remaining_accounts)?;
+        Ok(())
+    }
+    pub fn compound<'info>(
+        ctx: Context<'_, '_, '_, 'info, Compound<'info>>,
+        _position_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.compound(ctx.remaining_accounts)?;
+        Ok(())
+    }
+    pub fn fund_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, FundRewards<'info>>,
+        city_name: String,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.fund_rewards(city_name, amount, ctx.remaining_accounts)?;
+        Ok(())
+    }
+    pub fn deposit_yield<'info>(
+        ctx: Context<'_, '_, '_, 'info, FundRewards<'info>>,
+        city_name: String,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.deposit_yield(city_name, amount, ctx.remaining_accounts)?;
+        Ok(())
+    }
+    pub fn set_reward_mint(ctx: Context<SetRewardMint>, city_name: String) -> Result<()> {
+        ctx.accounts.set_reward_mint(city_name)?;
+        Ok(())
+    }
+    pub fn set_reward_price(
+        ctx: Context<SetRewardPrice>,
+        city_name: String,
+        price_usd: u64,
+    ) -> Result<()> {
+        ctx.accounts.set_reward_price(city_name, price_usd)?;
+        Ok(())
+    }
+    pub fn sweep_penalties<'info>(
+        ctx: Context<'_, '_, '_, 'info, SweepPenalties<'info>>,
+        city_name: String,
+        amount: u64,
+    ) -> Result<()> {
         ctx.accounts
-            .claim_reward(city_price_usd, sol_price_usd)?;
+            .sweep_penalties(city_name, amount, ctx.remaining_accounts)?;
         Ok(())
     }
-    pub fn close_stake(ctx: Context<CloseStake>) -> Result<()> {
-        ctx.accounts.close_stake()?;
+    pub fn stake_at(ctx: Context<StakeAt>, slot: u64) -> Result<StakeSnapshot> {
+        ctx.accounts.stake_at(slot)
+    }
+    pub fn close_stake<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseStake<'info>>,
+        _position_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.close_stake(ctx.remaining_accounts)?;
         Ok(())
     }
 }
@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
 
+use crate::error::RwaError;
+use crate::state::ProtocolConfig;
+
 #[account]
 #[derive(InitSpace)]
 pub struct CityConfig {
@@ -10,4 +13,92 @@ pub struct CityConfig {
     pub bump: u8,
     #[max_len(256)]
     pub metadata_uri: String,
+    pub status: CityStatus,
+    /// Per-city fee overrides; `None` falls back to `ProtocolConfig`.
+    pub buy_fee_bps: Option<u16>,
+    pub sell_fee_bps: Option<u16>,
+    /// This city's share of `Vault.liabilities`: net lamports paid in for its
+    /// outstanding supply.
+    pub liabilities: u64,
+}
+
+impl CityConfig {
+    pub fn buy_fee_bps(&self, config: &ProtocolConfig) -> u16 {
+        self.buy_fee_bps.unwrap_or(config.buy_fee_bps)
+    }
+
+    pub fn sell_fee_bps(&self, config: &ProtocolConfig) -> u16 {
+        self.sell_fee_bps.unwrap_or(config.sell_fee_bps)
+    }
+
+    /// Records `amount` newly minted tokens backed by `lamports` of liabilities.
+    pub fn issue(&mut self, amount: u64, lamports: u64) -> Result<()> {
+        self.total_supply = self
+            .total_supply
+            .checked_add(amount)
+            .ok_or(RwaError::Overflow)?;
+        self.liabilities = self
+            .liabilities
+            .checked_add(lamports)
+            .ok_or(RwaError::Overflow)?;
+        Ok(())
+    }
+
+    /// Retires `amount` burned tokens and returns the liabilities they carried,
+    /// a pro-rata share so the last token out releases whatever is left.
+    pub fn retire(&mut self, amount: u64) -> Result<u64> {
+        require!(amount <= self.total_supply, RwaError::Overflow);
+        let released = (self.liabilities as u128 * amount as u128)
+            .checked_div(self.total_supply as u128)
+            .unwrap_or(0) as u64;
+        self.total_supply -= amount;
+        self.liabilities -= released;
+        Ok(released)
+    }
+}
+
+/// Lifecycle of a listed city, used to wind a property down.
+///
+/// | status    | buy | sell | stake |
+/// |-----------|-----|------|-------|
+/// | Active    | yes | yes  | yes   |
+/// | BuyPaused | no  | yes  | yes   |
+/// | SellOnly  | no  | yes  | no    |
+/// | Delisted  | no  | no   | no    |
+/// | Redeemed  | no  | yes  | no    |
+///
+/// `Redeemed` is terminal and freezes the city price so holders exit at the
+/// final valuation.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum CityStatus {
+    Active,
+    BuyPaused,
+    SellOnly,
+    Delisted,
+    Redeemed,
+}
+
+impl CityStatus {
+    pub fn can_buy(self) -> bool {
+        self == CityStatus::Active
+    }
+
+    pub fn can_sell(self) -> bool {
+        self != CityStatus::Delisted
+    }
+
+    pub fn can_stake(self) -> bool {
+        matches!(self, CityStatus::Active | CityStatus::BuyPaused)
+    }
+
+    pub fn can_transition_to(self, next: CityStatus) -> bool {
+        use CityStatus::*;
+        match (self, next) {
+            (Redeemed, _) => false,
+            (Delisted, Redeemed) => true,
+            (Delisted, _) => false,
+            (_, Redeemed) => false,
+            (current, next) => current != next,
+        }
+    }
 }
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::Field;
+
+/// Longest value accepted for a non-URI metadata field.
+pub const MAX_METADATA_FIELD_LEN: usize = 64;
+
+/// Fields of a city's in-mint token metadata the admin can set.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CityMetadataField {
+    Name,
+    Symbol,
+    Uri,
+    PropertyAddress,
+    SquareMetres,
+    ValuationDate,
+}
+
+impl CityMetadataField {
+    pub fn max_len(self) -> usize {
+        match self {
+            CityMetadataField::Uri => 256,
+            _ => MAX_METADATA_FIELD_LEN,
+        }
+    }
+
+    pub fn to_field(self) -> Field {
+        match self {
+            CityMetadataField::Name => Field::Name,
+            CityMetadataField::Symbol => Field::Symbol,
+            CityMetadataField::Uri => Field::Uri,
+            CityMetadataField::PropertyAddress => Field::Key("property_address".to_string()),
+            CityMetadataField::SquareMetres => Field::Key("square_metres".to_string()),
+            CityMetadataField::ValuationDate => Field::Key("valuation_date".to_string()),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+use crate::utils::OraclePrice;
+
+/// Program-owned USD price of one whole city token, kept by the admin.
+#[account]
+#[derive(InitSpace)]
+pub struct CityPrice {
+    /// USD with `PRICE_DECIMALS` decimals.
+    pub price: u64,
+    pub conf: u64,
+    pub last_updated: i64,
+    pub bump: u8,
+}
+
+impl CityPrice {
+    pub fn oracle_price(&self) -> OraclePrice {
+        OraclePrice {
+            price: self.price,
+            conf: self.conf,
+            publish_time: self.last_updated,
+        }
+    }
+}
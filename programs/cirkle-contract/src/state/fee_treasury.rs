@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+/// Collects trading fees, kept apart from the SOL backing city tokens.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeTreasury {
+    pub balance: u64,
+    pub total_collected: u64,
+    pub bump: u8,
+}
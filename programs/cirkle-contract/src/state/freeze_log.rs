@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+/// Numbers the freeze and thaw actions taken on a holder's city-token account.
+#[account]
+#[derive(InitSpace)]
+pub struct FreezeLog {
+    pub token_account: Pubkey,
+    /// Number of `FreezeRecord`s written, and the sequence of the next one.
+    pub count: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+/// One freeze or thaw of a holder's city-token account. Records are never
+/// overwritten, so every action stays on chain.
+#[account]
+#[derive(InitSpace)]
+pub struct FreezeRecord {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub city_mint: Pubkey,
+    /// Position of this action in the account's `FreezeLog`.
+    pub sequence: u64,
+    pub frozen: bool,
+    /// Compliance reason for the action, e.g. a court order or sanctions listing.
+    pub reason_code: u16,
+    pub authority: Pubkey,
+    pub updated_at: i64,
+    pub bump: u8,
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+
+pub const MAX_BLOCKED_JURISDICTIONS: usize = 16;
+
+/// Jurisdictions whose holders may not send or receive a city's token,
+/// enforced by the transfer hook. Minting skips the hook, so buys, stakes and
+/// compounds check the caller's jurisdiction themselves.
+#[account]
+#[derive(InitSpace)]
+pub struct JurisdictionRules {
+    pub city_mint: Pubkey,
+    /// ISO 3166-1 alpha-2 country codes.
+    #[max_len(MAX_BLOCKED_JURISDICTIONS)]
+    pub blocked: Vec<[u8; 2]>,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl JurisdictionRules {
+    pub fn require_allowed(&self, jurisdiction: [u8; 2]) -> Result<()> {
+        require!(
+            !self.blocked.contains(&jurisdiction),
+            RwaError::JurisdictionBlocked
+        );
+        Ok(())
+    }
+}
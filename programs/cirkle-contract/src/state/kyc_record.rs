@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+
+/// Compliance status of a wallet, issued by `ProtocolConfig.compliance_authority`.
+#[account]
+#[derive(InitSpace)]
+pub struct KycRecord {
+    pub wallet: Pubkey,
+    pub status: KycStatus,
+    /// ISO 3166-1 alpha-2 country code, e.g. `*b"US"`.
+    pub jurisdiction: [u8; 2],
+    pub expires_at: i64,
+    pub issued_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl KycRecord {
+    pub fn require_valid(&self, now: i64) -> Result<()> {
+        require!(self.status == KycStatus::Verified, RwaError::KycNotVerified);
+        require!(now < self.expires_at, RwaError::KycExpired);
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum KycStatus {
+    Verified,
+    Suspended,
+    Revoked,
+}
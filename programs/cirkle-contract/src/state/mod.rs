@@ -5,4 +5,34 @@ pub mod user_stake;
 pub use user_stake::*;
 
 pub mod city_config;
-pub use city_config::*;
\ No newline at end of file
+pub use city_config::*;
+
+pub mod city_price;
+pub use city_price::*;
+
+pub mod protocol_config;
+pub use protocol_config::*;
+
+pub mod fee_treasury;
+pub use fee_treasury::*;
+
+pub mod stake_pool;
+pub use stake_pool::*;
+
+pub mod stake_history;
+pub use stake_history::*;
+
+pub mod kyc_record;
+pub use kyc_record::*;
+
+pub mod freeze_record;
+pub use freeze_record::*;
+
+pub mod freeze_log;
+pub use freeze_log::*;
+
+pub mod jurisdiction_rules;
+pub use jurisdiction_rules::*;
+
+pub mod city_metadata;
+pub use city_metadata::*;
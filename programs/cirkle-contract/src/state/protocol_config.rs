@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{FEATURE_REWARDS, FEATURE_STAKING, FEATURE_TRADING};
+use crate::error::RwaError;
+
+/// Singleton holding the canonical admin and protocol-wide settings.
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolConfig {
+    pub admin: Pubkey,
+    /// Can pause the protocol during an incident; only the admin can unpause.
+    pub guardian: Pubkey,
+    pub vault: Pubkey,
+    /// Pyth SOL/USD price account used for buy and sell.
+    pub sol_usd_feed: Pubkey,
+    pub max_price_age: u64,
+    pub max_confidence_bps: u16,
+    pub buy_fee_bps: u16,
+    pub sell_fee_bps: u16,
+    /// Minimum `Vault.balance / Vault.liabilities` kept after admin
+    /// withdrawals and reward payouts, in basis points.
+    pub min_reserve_ratio_bps: u16,
+    /// Bitmask of `FEATURE_*` switches that are turned on.
+    pub features: u8,
+    /// Bitmask of `FEATURE_*` switches paused by the guardian.
+    pub paused: u8,
+    pub bump: u8,
+    /// Issues and revokes `KycRecord`s.
+    pub compliance_authority: Pubkey,
+    /// Maximum age of a `CityPrice`; `max_price_age` applies to SOL/USD.
+    pub max_city_price_age: u64,
+}
+
+impl ProtocolConfig {
+    pub fn require_feature(&self, feature: u8) -> Result<()> {
+        require!(
+            self.features & feature == feature,
+            RwaError::FeatureDisabled
+        );
+        self.require_not_paused(feature)
+    }
+
+    pub fn require_not_paused(&self, feature: u8) -> Result<()> {
+        require!(
+            self.paused & feature & FEATURE_TRADING == 0,
+            RwaError::TradingPaused
+        );
+        require!(
+            self.paused & feature & FEATURE_STAKING == 0,
+            RwaError::StakingPaused
+        );
+        require!(
+            self.paused & feature & FEATURE_REWARDS == 0,
+            RwaError::RewardsPaused
+        );
+        Ok(())
+    }
+}
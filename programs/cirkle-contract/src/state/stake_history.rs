@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+
+/// Checkpoints kept, one per slot the amount changed in. Anyone can move the
+/// city total, so the pool keeps enough that pushing a past slot out of its
+/// log takes hundreds of transactions in separate slots.
+pub const MAX_POOL_CHECKPOINTS: usize = 512;
+pub const MAX_USER_CHECKPOINTS: usize = 32;
+
+/// Staked amount from the end of `slot` until the next checkpoint.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct Checkpoint {
+    pub slot: u64,
+    pub amount: u64,
+}
+
+/// A user's total stake in a city across all positions, with its history.
+#[account]
+#[derive(InitSpace)]
+pub struct StakeHistory {
+    pub owner: Pubkey,
+    pub city_mint: Pubkey,
+    pub staked_amount: u64,
+    pub bump: u8,
+    #[max_len(MAX_USER_CHECKPOINTS)]
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+impl StakeHistory {
+    pub fn add(&mut self, amount: u64, slot: u64) -> Result<()> {
+        self.staked_amount = self
+            .staked_amount
+            .checked_add(amount)
+            .ok_or(RwaError::Overflow)?;
+        push_checkpoint(
+            &mut self.checkpoints,
+            MAX_USER_CHECKPOINTS,
+            slot,
+            self.staked_amount,
+        );
+        Ok(())
+    }
+
+    pub fn sub(&mut self, amount: u64, slot: u64) -> Result<()> {
+        self.staked_amount = self.staked_amount.saturating_sub(amount);
+        push_checkpoint(
+            &mut self.checkpoints,
+            MAX_USER_CHECKPOINTS,
+            slot,
+            self.staked_amount,
+        );
+        Ok(())
+    }
+
+    pub fn staked_at(&self, slot: u64) -> Result<u64> {
+        amount_at(&self.checkpoints, MAX_USER_CHECKPOINTS, slot)
+    }
+}
+
+/// Records `amount` as of `slot`, overwriting that slot's checkpoint if it
+/// has one and otherwise dropping the oldest checkpoint when full.
+pub fn push_checkpoint(checkpoints: &mut Vec<Checkpoint>, max: usize, slot: u64, amount: u64) {
+    if let Some(last) = checkpoints.last_mut() {
+        if last.slot == slot {
+            last.amount = amount;
+            return;
+        }
+    }
+    if checkpoints.len() >= max {
+        checkpoints.remove(0);
+    }
+    checkpoints.push(Checkpoint { slot, amount });
+}
+
+/// Amount in effect at the end of `slot`. A full log may have dropped the
+/// checkpoint covering an old slot, in which case the answer is unknown.
+pub fn amount_at(checkpoints: &[Checkpoint], max: usize, slot: u64) -> Result<u64> {
+    let idx = checkpoints.partition_point(|c| c.slot <= slot);
+    if idx > 0 {
+        return Ok(checkpoints[idx - 1].amount);
+    }
+    require!(checkpoints.len() < max, RwaError::CheckpointUnavailable);
+    Ok(0)
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{ACC_PRECISION, BPS_DENOMINATOR, SECONDS_PER_YEAR};
+use crate::error::RwaError;
+use crate::state::{amount_at, push_checkpoint, Checkpoint, MAX_POOL_CHECKPOINTS};
+
+/// Per-city staking pool holding the global reward-per-token index.
+///
+/// Rewards accrue in base units of `reward_mint` and are paid from the pool's
+/// associated token account, so the index does not depend on prices.
+#[account]
+#[derive(InitSpace)]
+pub struct StakePool {
+    pub city_mint: Pubkey,
+    pub total_staked: u64,
+    /// Rewards per staked token since the pool was created, scaled by `ACC_PRECISION`.
+    pub acc_reward_per_token: u128,
+    pub last_update: i64,
+    pub bump: u8,
+    /// Yearly reward rate in basis points of the staked amount. Only non-zero
+    /// while rewards are paid in the city token.
+    pub reward_rate_bps: u16,
+    pub last_rate_change: i64,
+    /// Penalty for unstaking a locked position early; `None` rejects early exits.
+    pub early_exit_penalty_bps: Option<u16>,
+    /// Mint rewards are paid in; defaults to the city mint.
+    pub reward_mint: Pubkey,
+    /// USD value of one whole reward token with `PRICE_DECIMALS`, set by the
+    /// admin so rewards in mints without an oracle can be compounded.
+    pub reward_price_usd: u64,
+    /// Reward tokens held by the pool's reward account.
+    pub reward_balance: u64,
+    /// Settled rewards not yet paid out, summed over every position.
+    pub unclaimed_rewards: u64,
+    /// Early-exit penalties held in the pool's city-token account while
+    /// rewards are paid in another mint; the admin can sweep them.
+    pub penalty_balance: u64,
+    /// Sum of position weights, the denominator for the fixed reward rate.
+    pub total_weight: u128,
+    /// Distributed yield per staked token, scaled by `ACC_PRECISION`. Lock
+    /// multipliers do not apply to it.
+    pub acc_yield_per_token: u128,
+    /// Seconds unstaked tokens wait before they can be withdrawn.
+    pub unbonding_period: i64,
+    /// `total_staked` at the end of each slot it changed in.
+    #[max_len(MAX_POOL_CHECKPOINTS)]
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+impl StakePool {
+    pub fn signer_seeds(&self) -> [&[u8]; 3] {
+        [
+            b"stake-pool",
+            self.city_mint.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+
+    /// Accrues the index up to `now` at the current rate. Must be called
+    /// before the rate changes so earlier time is not paid at the new rate.
+    pub fn update(&mut self, now: i64) -> Result<()> {
+        let elapsed = now.saturating_sub(self.last_update).max(0) as u128;
+        if elapsed > 0 {
+            let accrued = elapsed
+                .checked_mul(self.reward_rate_bps as u128)
+                .and_then(|v| v.checked_mul(ACC_PRECISION))
+                .ok_or(RwaError::Overflow)?
+                / (BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128);
+            self.acc_reward_per_token = self
+                .acc_reward_per_token
+                .checked_add(accrued)
+                .ok_or(RwaError::Overflow)?;
+        }
+        self.last_update = now;
+        Ok(())
+    }
+
+    /// Records the current `total_staked` for `slot`.
+    pub fn checkpoint(&mut self, slot: u64) {
+        push_checkpoint(&mut self.checkpoints, MAX_POOL_CHECKPOINTS, slot, self.total_staked);
+    }
+
+    pub fn total_staked_at(&self, slot: u64) -> Result<u64> {
+        amount_at(&self.checkpoints, MAX_POOL_CHECKPOINTS, slot)
+    }
+
+    /// Spreads `amount` over current stakers pro rata to their staked
+    /// tokens, so depositing needs no per-position writes. Rental yield is
+    /// income per token held, so lock multipliers do not boost it.
+    pub fn distribute(&mut self, amount: u64) -> Result<()> {
+        require!(self.total_staked > 0, RwaError::NothingStaked);
+
+        let per_token = (amount as u128)
+            .checked_mul(ACC_PRECISION)
+            .ok_or(RwaError::Overflow)?
+            / self.total_staked as u128;
+        self.acc_yield_per_token = self
+            .acc_yield_per_token
+            .checked_add(per_token)
+            .ok_or(RwaError::Overflow)?;
+        Ok(())
+    }
+}
@@ -1,5 +1,9 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::{ACC_PRECISION, BPS_DENOMINATOR, SECONDS_PER_DAY};
+use crate::error::RwaError;
+use crate::state::StakePool;
+
 #[account]
 #[derive(InitSpace)]
 pub struct UserStake {
@@ -7,6 +11,208 @@ pub struct UserStake {
     pub city_mint: Pubkey,
     pub staked_amount: u64,
     pub stake_start: i64,
-    pub vault_ata: Pubkey, 
+    pub vault_ata: Pubkey,
     pub bump: u8,
+    /// `weight * acc_reward_per_token` at the last settlement.
+    pub reward_debt: u128,
+    /// `staked_amount * acc_yield_per_token` at the last settlement.
+    pub yield_debt: u128,
+    /// Settled but unclaimed rewards, in base units of the pool's `reward_mint`.
+    pub pending_rewards: u64,
+    pub lock_tier: LockTier,
+    /// Unstaking before this time pays the pool's early-exit penalty.
+    pub lock_end: i64,
+    pub reward_multiplier_bps: u16,
+    /// Distinguishes a user's positions in the same city.
+    pub position_id: u64,
+    /// Weighted stake currently counted in `StakePool.total_weight`.
+    pub weight: u128,
+    /// Tokens waiting out the cooldown; they no longer earn rewards.
+    pub unbonding_amount: u64,
+    pub unbonding_end: i64,
+}
+
+impl UserStake {
+    /// Moves rewards accrued since the last settlement into `pending_rewards`
+    /// and drops the lock boost once the lock has ended. The pool must already
+    /// be updated; call `sync_reward_debt` after changing `staked_amount`.
+    pub fn settle(&mut self, pool: &mut StakePool, now: i64) -> Result<()> {
+        let rewards = self
+            .weight
+            .checked_mul(pool.acc_reward_per_token)
+            .ok_or(RwaError::Overflow)?
+            .saturating_sub(self.reward_debt)
+            / ACC_PRECISION;
+        let yields = (self.staked_amount as u128)
+            .checked_mul(pool.acc_yield_per_token)
+            .ok_or(RwaError::Overflow)?
+            .saturating_sub(self.yield_debt)
+            / ACC_PRECISION;
+        let accrued = rewards.checked_add(yields).ok_or(RwaError::Overflow)?;
+        let accrued = u64::try_from(accrued).map_err(|_| error!(RwaError::Overflow))?;
+        self.pending_rewards = self
+            .pending_rewards
+            .checked_add(accrued)
+            .ok_or(RwaError::Overflow)?;
+        pool.unclaimed_rewards = pool
+            .unclaimed_rewards
+            .checked_add(accrued)
+            .ok_or(RwaError::Overflow)?;
+        self.expire_lock(now);
+        self.sync_reward_debt(pool)
+    }
+
+    /// Falls back to `Flexible` terms once `lock_end` has passed, so an
+    /// ended lock stops boosting the position's weight.
+    fn expire_lock(&mut self, now: i64) {
+        if self.lock_tier != LockTier::Flexible && !self.is_locked(now) {
+            self.lock_tier = LockTier::Flexible;
+            self.reward_multiplier_bps = LockTier::Flexible.multiplier_bps();
+        }
+    }
+
+    /// Deducts `amount` of rewards paid out of the pool from the position.
+    pub fn take_rewards(&mut self, pool: &mut StakePool, amount: u64) {
+        self.pending_rewards -= amount;
+        pool.unclaimed_rewards = pool.unclaimed_rewards.saturating_sub(amount);
+        pool.reward_balance -= amount;
+    }
+
+    /// Records the current weight in the pool and resets both debts to it.
+    pub fn sync_reward_debt(&mut self, pool: &mut StakePool) -> Result<()> {
+        let weight = self.weighted_amount();
+        pool.total_weight = pool
+            .total_weight
+            .checked_sub(self.weight)
+            .and_then(|v| v.checked_add(weight))
+            .ok_or(RwaError::Overflow)?;
+        self.weight = weight;
+        self.reward_debt = weight
+            .checked_mul(pool.acc_reward_per_token)
+            .ok_or(RwaError::Overflow)?;
+        self.yield_debt = (self.staked_amount as u128)
+            .checked_mul(pool.acc_yield_per_token)
+            .ok_or(RwaError::Overflow)?;
+        Ok(())
+    }
+
+    /// Adds `amount` under `lock_tier`, settling what the old balance earned
+    /// first. A top-up may extend the lock but never shorten it. The pool must
+    /// already be updated.
+    pub fn add_stake(
+        &mut self,
+        pool: &mut StakePool,
+        amount: u64,
+        lock_tier: LockTier,
+        now: i64,
+    ) -> Result<()> {
+        self.settle(pool, now)?;
+        require!(
+            self.staked_amount == 0 || lock_tier >= self.lock_tier,
+            RwaError::LockTierDowngrade
+        );
+
+        let lock_end = now
+            .checked_add(lock_tier.duration())
+            .ok_or(RwaError::Overflow)?;
+        if self.staked_amount == 0 {
+            self.stake_start = now;
+            self.lock_end = lock_end;
+        } else {
+            self.lock_end = self.lock_end.max(lock_end);
+        }
+        self.lock_tier = lock_tier;
+        self.reward_multiplier_bps = lock_tier.multiplier_bps();
+
+        self.staked_amount = self
+            .staked_amount
+            .checked_add(amount)
+            .ok_or(RwaError::Overflow)?;
+        self.sync_reward_debt(pool)?;
+        pool.total_staked = pool
+            .total_staked
+            .checked_add(amount)
+            .ok_or(RwaError::Overflow)?;
+        Ok(())
+    }
+
+    /// Removes `amount` from the position after settling its rewards.
+    pub fn remove_stake(&mut self, pool: &mut StakePool, amount: u64, now: i64) -> Result<()> {
+        require!(
+            self.staked_amount >= amount,
+            RwaError::InsufficientStakedAmount
+        );
+        self.settle(pool, now)?;
+
+        self.staked_amount -= amount;
+        self.sync_reward_debt(pool)?;
+        pool.total_staked = pool.total_staked.saturating_sub(amount);
+
+        if self.staked_amount == 0 {
+            self.stake_start = 0;
+            self.lock_end = 0;
+        }
+        Ok(())
+    }
+
+    /// Staked amount boosted by the lock tier multiplier.
+    pub fn weighted_amount(&self) -> u128 {
+        self.staked_amount as u128 * self.reward_multiplier_bps as u128 / BPS_DENOMINATOR as u128
+    }
+
+    pub fn is_locked(&self, now: i64) -> bool {
+        now < self.lock_end
+    }
+}
+
+/// Lock periods a stake can commit to in exchange for boosted rewards.
+///
+/// | tier      | lock     | multiplier |
+/// |-----------|----------|------------|
+/// | Flexible  | none     | 1.00x      |
+/// | Days30    | 30 days  | 1.10x      |
+/// | Days90    | 90 days  | 1.25x      |
+/// | Days180   | 180 days | 1.50x      |
+/// | Days365   | 365 days | 2.00x      |
+#[derive(
+    AnchorSerialize,
+    AnchorDeserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    InitSpace,
+    Debug,
+)]
+pub enum LockTier {
+    Flexible,
+    Days30,
+    Days90,
+    Days180,
+    Days365,
+}
+
+impl LockTier {
+    pub fn duration(self) -> i64 {
+        let days = match self {
+            LockTier::Flexible => 0,
+            LockTier::Days30 => 30,
+            LockTier::Days90 => 90,
+            LockTier::Days180 => 180,
+            LockTier::Days365 => 365,
+        };
+        days * SECONDS_PER_DAY
+    }
+
+    pub fn multiplier_bps(self) -> u16 {
+        match self {
+            LockTier::Flexible => 10_000,
+            LockTier::Days30 => 11_000,
+            LockTier::Days90 => 12_500,
+            LockTier::Days180 => 15_000,
+            LockTier::Days365 => 20_000,
+        }
+    }
 }
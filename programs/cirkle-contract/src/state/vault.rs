@@ -1,9 +1,38 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::BPS_DENOMINATOR;
+
 #[account]
 #[derive(InitSpace)]
 pub struct Vault {
     pub authority: Pubkey,
     pub balance: u64,
     pub bump: u8,
+    /// Net lamports paid in for outstanding city tokens, i.e. what holders
+    /// could claim back by selling. The sum of every `CityConfig.liabilities`.
+    pub liabilities: u64,
+}
+
+impl Vault {
+    /// `balance / liabilities` in basis points; `u64::MAX` with no liabilities.
+    pub fn reserve_ratio_bps(&self) -> u64 {
+        reserve_ratio_bps(self.balance, self.liabilities)
+    }
+
+    /// Seeds for signing as the vault PDA.
+    pub fn signer_seeds(&self) -> [&[u8]; 3] {
+        [
+            b"protocol_admin",
+            self.authority.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
+
+pub fn reserve_ratio_bps(balance: u64, liabilities: u64) -> u64 {
+    if liabilities == 0 {
+        return u64::MAX;
+    }
+    let ratio = balance as u128 * BPS_DENOMINATOR as u128 / liabilities as u128;
+    u64::try_from(ratio).unwrap_or(u64::MAX)
 }
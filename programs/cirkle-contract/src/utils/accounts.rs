@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+
+/// Deserializes a program account that is allowed to be missing, failing
+/// with `missing` instead of Anchor's `AccountNotInitialized`.
+pub fn load_account<T: AccountDeserialize + Owner>(
+    info: &AccountInfo,
+    missing: RwaError,
+) -> Result<T> {
+    if info.owner != &T::owner() || info.data_is_empty() {
+        return Err(missing.into());
+    }
+    let data = info.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..])
+}
+
+/// Writes back an account loaded with `load_account`.
+pub fn save_account<T: AccountSerialize>(account: &T, info: &AccountInfo) -> Result<()> {
+    let mut data = info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    account.try_serialize(&mut writer)
+}
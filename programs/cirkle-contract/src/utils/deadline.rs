@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+
+/// Rejects the instruction once `deadline` (unix seconds), if given, has passed.
+pub fn check_deadline(deadline: Option<i64>, now: i64) -> Result<()> {
+    if let Some(deadline) = deadline {
+        require!(now <= deadline, RwaError::DeadlineExceeded);
+    }
+    Ok(())
+}
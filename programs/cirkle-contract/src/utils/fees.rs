@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::RwaError;
+
+/// Fee on `amount` at `fee_bps`, rounded up in the protocol's favour.
+pub fn fee_amount(amount: u64, fee_bps: u16) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(fee_bps as u128)
+        .and_then(|v| v.checked_add(BPS_DENOMINATOR as u128 - 1))
+        .ok_or(RwaError::Overflow)?
+        / BPS_DENOMINATOR as u128;
+
+    u64::try_from(fee).map_err(|_| error!(RwaError::Overflow))
+}
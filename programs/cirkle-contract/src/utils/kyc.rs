@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+use crate::error::RwaError;
+use crate::state::KycRecord;
+use crate::utils::load_account;
+
+/// Fails unless `info` holds a verified, unexpired `KycRecord`, which is
+/// returned for further checks.
+pub fn require_kyc(info: &AccountInfo, now: i64) -> Result<KycRecord> {
+    let record: KycRecord = load_account(info, RwaError::KycRequired)?;
+    record.require_valid(now)?;
+    Ok(record)
+}
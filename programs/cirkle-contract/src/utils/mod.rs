@@ -0,0 +1,20 @@
+pub mod accounts;
+pub use accounts::*;
+
+pub mod deadline;
+pub use deadline::*;
+
+pub mod fees;
+pub use fees::*;
+
+pub mod oracle;
+pub use oracle::*;
+
+pub mod vault;
+pub use vault::*;
+
+pub mod kyc;
+pub use kyc::*;
+
+pub mod token;
+pub use token::*;
@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{BPS_DENOMINATOR, LAMPORTS_PER_SOL, PRICE_DECIMALS, TOKEN_DECIMALS};
+use crate::error::RwaError;
+
+// Layout of a legacy Pyth price account (pyth-sdk-solana `SolanaPriceAccount`).
+const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+const PYTH_VERSION: u32 = 2;
+const PYTH_ACCOUNT_TYPE_PRICE: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+
+const MAGIC_OFFSET: usize = 0;
+const VERSION_OFFSET: usize = 4;
+const ACCOUNT_TYPE_OFFSET: usize = 8;
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+const PYTH_MIN_LEN: usize = 240;
+
+/// A price normalised to `PRICE_DECIMALS`, ready to be validated.
+#[derive(Clone, Copy, Debug)]
+pub struct OraclePrice {
+    pub price: u64,
+    pub conf: u64,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// Reads the aggregate price from a Pyth-format price account.
+    pub fn from_pyth(info: &AccountInfo) -> Result<Self> {
+        let data = info.try_borrow_data()?;
+        require!(data.len() >= PYTH_MIN_LEN, RwaError::InvalidPriceFeed);
+
+        require!(
+            read_u32(&data, MAGIC_OFFSET) == PYTH_MAGIC
+                && read_u32(&data, VERSION_OFFSET) == PYTH_VERSION
+                && read_u32(&data, ACCOUNT_TYPE_OFFSET) == PYTH_ACCOUNT_TYPE_PRICE,
+            RwaError::InvalidPriceFeed
+        );
+        require!(
+            read_u32(&data, AGG_STATUS_OFFSET) == PYTH_STATUS_TRADING,
+            RwaError::InvalidPrice
+        );
+
+        let expo = read_u32(&data, EXPO_OFFSET) as i32;
+        let price = read_u64(&data, AGG_PRICE_OFFSET) as i64;
+        let conf = read_u64(&data, AGG_CONF_OFFSET);
+        let publish_time = read_u64(&data, TIMESTAMP_OFFSET) as i64;
+
+        require!(price > 0, RwaError::InvalidPrice);
+
+        Ok(Self {
+            price: normalise(price as u64, expo)?,
+            conf: normalise(conf, expo)?,
+            publish_time,
+        })
+    }
+
+    /// Rejects prices older than `max_age` seconds or whose confidence
+    /// interval is wider than `max_confidence_bps` of the price.
+    pub fn validated(&self, now: i64, max_age: u64, max_confidence_bps: u16) -> Result<u64> {
+        require!(self.price > 0, RwaError::InvalidPrice);
+
+        let age = now.saturating_sub(self.publish_time).max(0) as u64;
+        require!(age <= max_age, RwaError::StalePrice);
+
+        let max_conf = (self.price as u128)
+            .checked_mul(max_confidence_bps as u128)
+            .ok_or(RwaError::Overflow)?
+            / BPS_DENOMINATOR as u128;
+        require!(
+            (self.conf as u128) <= max_conf,
+            RwaError::PriceConfidenceTooLow
+        );
+
+        Ok(self.price)
+    }
+}
+
+/// Tokens (with decimals) bought by `lamports` at the given USD prices.
+pub fn lamports_to_tokens(lamports: u64, sol_price_usd: u64, city_price_usd: u64) -> Result<u64> {
+    require!(city_price_usd > 0, RwaError::RateNotValid);
+
+    let tokens = (lamports as u128)
+        .checked_mul(sol_price_usd as u128)
+        .and_then(|v| v.checked_mul(10u128.pow(TOKEN_DECIMALS)))
+        .and_then(|v| v.checked_div(LAMPORTS_PER_SOL as u128 * city_price_usd as u128))
+        .ok_or(RwaError::Overflow)?;
+
+    u64::try_from(tokens).map_err(|_| error!(RwaError::Overflow))
+}
+
+/// Lamports returned for `token_amount` (with decimals) at the given USD prices.
+pub fn tokens_to_lamports(
+    token_amount: u64,
+    sol_price_usd: u64,
+    city_price_usd: u64,
+) -> Result<u64> {
+    require!(sol_price_usd > 0, RwaError::RateNotValid);
+
+    let lamports = (token_amount as u128)
+        .checked_mul(city_price_usd as u128)
+        .and_then(|v| v.checked_mul(LAMPORTS_PER_SOL as u128))
+        .and_then(|v| v.checked_div(10u128.pow(TOKEN_DECIMALS) * sol_price_usd as u128))
+        .ok_or(RwaError::Overflow)?;
+
+    u64::try_from(lamports).map_err(|_| error!(RwaError::Overflow))
+}
+
+/// Lamports needed to back `token_amount` (with decimals), rounded up.
+pub fn tokens_to_lamports_rounded_up(
+    token_amount: u64,
+    sol_price_usd: u64,
+    city_price_usd: u64,
+) -> Result<u64> {
+    require!(sol_price_usd > 0, RwaError::RateNotValid);
+
+    let lamports = (token_amount as u128)
+        .checked_mul(city_price_usd as u128)
+        .and_then(|v| v.checked_mul(LAMPORTS_PER_SOL as u128))
+        .ok_or(RwaError::Overflow)?
+        .div_ceil(10u128.pow(TOKEN_DECIMALS) * sol_price_usd as u128);
+
+    u64::try_from(lamports).map_err(|_| error!(RwaError::Overflow))
+}
+
+/// City tokens (with decimals) worth `amount` base units of a mint with
+/// `decimals`, priced at `price_usd` per whole token.
+pub fn value_to_tokens(
+    amount: u64,
+    decimals: u8,
+    price_usd: u64,
+    city_price_usd: u64,
+) -> Result<u64> {
+    require!(city_price_usd > 0, RwaError::RateNotValid);
+
+    let tokens = (amount as u128)
+        .checked_mul(price_usd as u128)
+        .and_then(|v| v.checked_mul(10u128.pow(TOKEN_DECIMALS)))
+        .and_then(|v| v.checked_div(10u128.pow(decimals as u32) * city_price_usd as u128))
+        .ok_or(RwaError::Overflow)?;
+
+    u64::try_from(tokens).map_err(|_| error!(RwaError::Overflow))
+}
+
+/// Rescales a raw `value * 10^expo` to `PRICE_DECIMALS`.
+fn normalise(value: u64, expo: i32) -> Result<u64> {
+    let shift = expo + PRICE_DECIMALS as i32;
+    let scaled = if shift >= 0 {
+        (value as u128).checked_mul(10u128.pow(shift as u32))
+    } else {
+        10u128
+            .checked_pow(shift.unsigned_abs())
+            .map(|d| value as u128 / d)
+    }
+    .ok_or(RwaError::Overflow)?;
+
+    u64::try_from(scaled).map_err(|_| error!(RwaError::Overflow))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+
+use crate::error::RwaError;
+use crate::state::{reserve_ratio_bps, Vault};
+
+// Every lamport movement in or out of the vault goes through this module so
+// that `Vault.balance` always equals the vault's lamports above rent exemption.
+
+/// Lamports the vault can pay out without dropping below rent exemption.
+pub fn vault_available(vault: &Account<Vault>) -> Result<u64> {
+    let info = vault.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(info.data_len());
+    Ok(info.lamports().saturating_sub(rent_exempt))
+}
+
+/// Re-derives `Vault.balance` from the vault's actual lamports.
+pub fn sync_vault_balance(vault: &mut Account<Vault>) -> Result<()> {
+    vault.balance = vault_available(vault)?;
+    Ok(())
+}
+
+/// Moves `amount` lamports from `payer` into the vault.
+pub fn deposit_to_vault<'info>(
+    vault: &mut Account<'info, Vault>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    invoke(
+        &system_instruction::transfer(payer.key, &vault.key(), amount),
+        &[
+            payer.clone(),
+            vault.to_account_info(),
+            system_program.clone(),
+        ],
+    )?;
+    sync_vault_balance(vault)
+}
+
+/// Pays `amount` lamports out of the vault to `recipient`.
+///
+/// The vault carries data, so the system program cannot debit it; lamports are
+/// moved directly, which the runtime allows for program-owned accounts.
+pub fn pay_from_vault(
+    vault: &mut Account<Vault>,
+    recipient: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, RwaError::InvalidAmount);
+    require!(
+        amount <= vault_available(vault)?,
+        RwaError::InsufficientFunds
+    );
+
+    let vault_info = vault.to_account_info();
+    **vault_info.try_borrow_mut_lamports()? = vault_info
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(RwaError::InsufficientFunds)?;
+    **recipient.try_borrow_mut_lamports()? = recipient
+        .lamports()
+        .checked_add(amount)
+        .ok_or(RwaError::Overflow)?;
+
+    sync_vault_balance(vault)
+}
+
+/// Fails if the vault holds less than `min_reserve_ratio_bps` of its liabilities.
+pub fn require_reserve_ratio(vault: &Vault, min_reserve_ratio_bps: u16) -> Result<()> {
+    require!(
+        vault.reserve_ratio_bps() >= min_reserve_ratio_bps as u64,
+        RwaError::ReserveRatioTooLow
+    );
+    Ok(())
+}
+
+/// Pays `amount` out of the vault for anything other than a sell, refusing
+/// payouts that leave the vault below `min_reserve_ratio_bps` of its liabilities.
+pub fn pay_from_vault_reserved(
+    vault: &mut Account<Vault>,
+    recipient: &AccountInfo,
+    amount: u64,
+    min_reserve_ratio_bps: u16,
+) -> Result<()> {
+    let remaining = vault_available(vault)?
+        .checked_sub(amount)
+        .ok_or(RwaError::InsufficientFunds)?;
+    require!(
+        reserve_ratio_bps(remaining, vault.liabilities) >= min_reserve_ratio_bps as u64,
+        RwaError::ReserveRatioTooLow
+    );
+
+    pay_from_vault(vault, recipient, amount)
+}
@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum HookError {
+    #[msg("Mint Does Not Use This Transfer Hook")]
+    InvalidMint,
+    #[msg("Hook Called Outside Of A Transfer")]
+    NotTransferring,
+}
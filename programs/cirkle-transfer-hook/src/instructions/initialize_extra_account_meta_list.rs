@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    get_mint_extension_data,
+    spl_token_2022::extension::transfer_hook::TransferHook as TransferHookExtension, Mint,
+};
+use spl_tlv_account_resolution::{
+    account::ExtraAccountMeta, pubkey_data::PubkeyData, seeds::Seed, state::ExtraAccountMetaList,
+};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+use crate::error::HookError;
+
+/// Source and destination owners, the Cirkle program, both owners' KYC
+/// records and the city's jurisdiction rules.
+pub const EXTRA_ACCOUNT_METAS_LEN: usize = 6;
+
+/// Token account layout: the owner follows the 32-byte mint.
+const OWNER_OFFSET: u8 = 32;
+
+/// Writes the accounts Token-2022 appends to every `transfer_hook` call for
+/// `mint`. Anyone may pay for it since the list is fixed.
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: TLV account written in `initialize_extra_account_meta_list`
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(EXTRA_ACCOUNT_METAS_LEN)?,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeExtraAccountMetaList<'info> {
+    pub fn initialize_extra_account_meta_list(&mut self) -> Result<()> {
+        let hook = get_mint_extension_data::<TransferHookExtension>(&self.mint.to_account_info())?;
+        require!(
+            Option::<Pubkey>::from(hook.program_id) == Some(crate::ID),
+            HookError::InvalidMint
+        );
+
+        let mut data = self.extra_account_meta_list.try_borrow_mut_data()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas()?)?;
+
+        msg!("Transfer hook accounts set for {}", self.mint.key());
+
+        Ok(())
+    }
+}
+
+/// Extra accounts follow the four `Execute` accounts and this list, so they
+/// start at index 5; index 7 is the Cirkle program that owns the PDAs.
+fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    let owner = |account_index| PubkeyData::AccountData {
+        account_index,
+        data_index: OWNER_OFFSET,
+    };
+    let kyc = |account_index| {
+        [
+            Seed::Literal {
+                bytes: b"kyc".to_vec(),
+            },
+            Seed::AccountData {
+                account_index,
+                data_index: OWNER_OFFSET,
+                length: 32,
+            },
+        ]
+    };
+    let rules = [
+        Seed::Literal {
+            bytes: b"jurisdiction-rules".to_vec(),
+        },
+        Seed::AccountKey { index: 1 },
+    ];
+
+    Ok(vec![
+        ExtraAccountMeta::new_with_pubkey_data(&owner(0), false, false)?,
+        ExtraAccountMeta::new_with_pubkey_data(&owner(2), false, false)?,
+        ExtraAccountMeta::new_with_pubkey(&cirkle_contract::ID, false, false)?,
+        ExtraAccountMeta::new_external_pda_with_seeds(7, &kyc(0), false, false)?,
+        ExtraAccountMeta::new_external_pda_with_seeds(7, &kyc(2), false, false)?,
+        ExtraAccountMeta::new_external_pda_with_seeds(7, &rules, false, false)?,
+    ])
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    spl_token_2022::{
+        extension::{
+            transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Account as SplTokenAccount,
+    },
+    Mint, TokenAccount,
+};
+use cirkle_contract::error::RwaError;
+use cirkle_contract::program::CirkleContract;
+use cirkle_contract::state::JurisdictionRules;
+use cirkle_contract::utils::{load_account, require_kyc};
+
+use crate::error::HookError;
+
+/// Accounts of the SPL `Execute` instruction followed by the extra accounts
+/// from `initialize_extra_account_meta_list`, in the same order.
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: owner or delegate of the source, verified by Token-2022
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: only its address is checked; Token-2022 has already read it
+    #[account(
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// CHECK: matched against the source token account
+    #[account(address = source_token.owner)]
+    pub source_owner: UncheckedAccount<'info>,
+
+    /// CHECK: matched against the destination token account
+    #[account(address = destination_token.owner)]
+    pub destination_owner: UncheckedAccount<'info>,
+
+    pub cirkle_program: Program<'info, CirkleContract>,
+
+    /// CHECK: KYC record of the source owner, loaded in `transfer_hook`
+    #[account(
+        seeds = [b"kyc", source_token.owner.as_ref()],
+        bump,
+        seeds::program = cirkle_program.key(),
+    )]
+    pub source_kyc: UncheckedAccount<'info>,
+
+    /// CHECK: KYC record of the destination owner, loaded in `transfer_hook`
+    #[account(
+        seeds = [b"kyc", destination_token.owner.as_ref()],
+        bump,
+        seeds::program = cirkle_program.key(),
+    )]
+    pub destination_kyc: UncheckedAccount<'info>,
+
+    /// CHECK: city rules written by `create_city`, loaded in `transfer_hook`
+    #[account(
+        seeds = [b"jurisdiction-rules", mint.key().as_ref()],
+        bump,
+        seeds::program = cirkle_program.key(),
+    )]
+    pub jurisdiction_rules: UncheckedAccount<'info>,
+}
+
+impl<'info> TransferHook<'info> {
+    pub fn transfer_hook(&self, _amount: u64) -> Result<()> {
+        self.require_transferring()?;
+
+        let rules: JurisdictionRules = load_account(
+            &self.jurisdiction_rules.to_account_info(),
+            RwaError::CityNotFound,
+        )?;
+        let now = Clock::get()?.unix_timestamp;
+        check_party(&self.source_owner, &self.source_kyc, &rules, now)?;
+        check_party(&self.destination_owner, &self.destination_kyc, &rules, now)?;
+
+        Ok(())
+    }
+
+    /// Token-2022 flags the source account while the hook runs, so direct
+    /// calls cannot pass themselves off as a transfer.
+    fn require_transferring(&self) -> Result<()> {
+        let info = self.source_token.to_account_info();
+        let data = info.try_borrow_data()?;
+        let account = StateWithExtensions::<SplTokenAccount>::unpack(&data)?;
+        let extension = account.get_extension::<TransferHookAccount>()?;
+        require!(
+            bool::from(extension.transferring),
+            HookError::NotTransferring
+        );
+        Ok(())
+    }
+}
+
+/// Holders need a valid KYC record from a jurisdiction the city allows.
+/// Stake positions and reward pools are owned by Cirkle itself and exempt.
+fn check_party(
+    owner: &AccountInfo,
+    kyc: &AccountInfo,
+    rules: &JurisdictionRules,
+    now: i64,
+) -> Result<()> {
+    if owner.owner == &cirkle_contract::ID {
+        return Ok(());
+    }
+    let record = require_kyc(kyc, now)?;
+    rules.require_allowed(record.jurisdiction)
+}
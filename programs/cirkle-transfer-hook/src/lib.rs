@@ -0,0 +1,29 @@
+#![allow(unexpected_cfgs)]
+
+use anchor_lang::prelude::*;
+use spl_discriminator::SplDiscriminate;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+declare_id!("9tDGgBveTVfNJTQjHqftwhyc5Z4Qw87KUKC94d4taLiy");
+mod error;
+mod instructions;
+
+use instructions::*;
+#[program]
+pub mod cirkle_transfer_hook {
+    use super::*;
+
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        ctx.accounts.initialize_extra_account_meta_list()?;
+        Ok(())
+    }
+
+    /// Called by Token-2022 on every transfer of a city mint.
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        ctx.accounts.transfer_hook(amount)?;
+        Ok(())
+    }
+}
@@ -1,19 +1,31 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { CirkleContract } from "../target/types/cirkle_contract";
-import { PublicKey } from "@solana/web3.js";
+import { CirkleTransferHook } from "../target/types/cirkle_transfer_hook";
+import { AccountMeta, PublicKey } from "@solana/web3.js";
 import * as assert from "assert";
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
-  getAccount,
-  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createTransferCheckedInstruction,
+  getAccount as getTokenAccount,
+  getAssociatedTokenAddressSync,
+  getTokenMetadata,
+  NATIVE_MINT,
+  TOKEN_2022_PROGRAM_ID,
 } from "@solana/spl-token";
-import { associatedAddress } from "@coral-xyz/anchor/dist/cjs/utils/token";
 
 describe("buy_token tests (SOL-based) - FIXED", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
   const program = anchor.workspace.CirkleContract as Program<CirkleContract>;
+  const hookProgram = anchor.workspace.CirkleTransferHook as Program<CirkleTransferHook>;
+
+  // City mints are Token-2022 mints with a transfer hook
+  const associatedAddress = ({ mint, owner }: { mint: PublicKey; owner: PublicKey }) =>
+    getAssociatedTokenAddressSync(mint, owner, true, TOKEN_2022_PROGRAM_ID);
+  const getAccount = (connection: anchor.web3.Connection, address: PublicKey) =>
+    getTokenAccount(connection, address, undefined, TOKEN_2022_PROGRAM_ID);
 
   let user = provider.wallet;
   let admin = anchor.web3.Keypair.generate();
@@ -21,8 +33,109 @@ describe("buy_token tests (SOL-based) - FIXED", () => {
   let vaultBump: number;
   const cityName = "TestCity";
 
-  // Price of SOL in USD (for test calculations)
+  // Mock Pyth SOL/USD account loaded from tests/fixtures/sol_usd_price.json
+  const solUsdFeed = new PublicKey("J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix");
+
+  // Price of SOL in USD (for test calculations), as stored in the fixture
   const solPriceUsd = new anchor.BN(200); // 1 SOL = $200
+  const microUsd = new anchor.BN(1_000_000);
+  // The fixture has a fixed publish time, so tests accept old SOL prices
+  const testMaxPriceAge = new anchor.BN(4_000_000_000);
+  // City prices keep the program's default age limit of a week
+  const defaultMaxCityPriceAge = new anchor.BN(7 * 86_400);
+  const maxConfidenceBps = 200;
+
+  let cityPricePda: PublicKey;
+  // Read before the tests configure the oracle
+  let initialMaxCityPriceAge: anchor.BN;
+
+  const setCityPrice = async (rateUsd: anchor.BN) => {
+    await program.methods
+      .setCityPrice(cityName, rateUsd.mul(microUsd), new anchor.BN(0))
+      .accountsPartial({
+        admin: admin.publicKey,
+        cityPrice: cityPricePda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+  };
+
+  const cityMintPda = () =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("city-mint"), Buffer.from(cityName)],
+      program.programId
+    )[0];
+  const userCityAta = () => associatedAddress({ mint: cityMintPda(), owner: user.publicKey });
+  const stakePoolPda = () =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("stake-pool"), cityMintPda().toBuffer()],
+      program.programId
+    )[0];
+  const positionPda = (positionId: number) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("stake"),
+        user.publicKey.toBuffer(),
+        cityMintPda().toBuffer(),
+        new anchor.BN(positionId).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  // Accounts Token-2022 needs to run the transfer hook, in any order
+  const hookAccounts = (sourceOwner: PublicKey, destinationOwner: PublicKey): AccountMeta[] => {
+    const kycPda = (owner: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("kyc"), owner.toBuffer()], program.programId)[0];
+    return [
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("extra-account-metas"), cityMintPda().toBuffer()],
+        hookProgram.programId
+      )[0],
+      sourceOwner,
+      destinationOwner,
+      program.programId,
+      kycPda(sourceOwner),
+      kycPda(destinationOwner),
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("jurisdiction-rules"), cityMintPda().toBuffer()],
+        program.programId
+      )[0],
+      hookProgram.programId,
+    ].map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }));
+  };
+
+  const configureOracle = async (
+    maxPriceAge: anchor.BN,
+    maxCityPriceAge: anchor.BN = defaultMaxCityPriceAge
+  ) => {
+    await program.methods
+      .configureOracle(solUsdFeed, maxPriceAge, maxConfidenceBps, maxCityPriceAge)
+      .accountsPartial({
+        admin: admin.publicKey,
+      })
+      .signers([admin])
+      .rpc();
+  };
+
+  // Rewards are paid in the city token, so the reward mint and its
+  // program are the city's
+  const compoundAccounts = () => ({
+    user: user.publicKey,
+    vault: vaultPda,
+    solUsdPrice: solUsdFeed,
+    cityPrice: cityPricePda,
+    cityMint: cityMintPda(),
+    cityConfig: PublicKey.findProgramAddressSync(
+      [Buffer.from("city-config"), Buffer.from(cityName)],
+      program.programId
+    )[0],
+    rewardMint: cityMintPda(),
+    // City-token rewards are restaked directly and never reach the vault
+    vaultRewardAta: null,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+    rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+  });
 
   before(async () => {
     // Derive vault PDA
@@ -45,17 +158,117 @@ describe("buy_token tests (SOL-based) - FIXED", () => {
       .vaultInitialize()
       .accountsPartial({
         admin: admin.publicKey,
+        // The test validator deploys the program with the provider as upgrade authority
+        upgradeAuthority: provider.wallet.publicKey,
+        programData: PublicKey.findProgramAddressSync(
+          [program.programId.toBuffer()],
+          new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+        )[0],
         adminVault: vaultPda,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([admin])
       .rpc();
     console.log("Vault initialized for admin:", admin.publicKey.toBase58());
+
+    [cityPricePda] = await PublicKey.findProgramAddress(
+      [Buffer.from("city-price"), Buffer.from(cityName)],
+      program.programId
+    );
+    initialMaxCityPriceAge = (
+      await program.account.protocolConfig.fetch(
+        PublicKey.findProgramAddressSync([Buffer.from("protocol-config")], program.programId)[0]
+      )
+    ).maxCityPriceAge;
+    await configureOracle(testMaxPriceAge);
+
+    const [cityMintPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("city-mint"), Buffer.from(cityName)],
+      program.programId
+    );
+    await program.methods
+      .createCity(cityName, "https://example.com/testcity.json")
+      .accountsPartial({
+        admin: admin.publicKey,
+        vault: vaultPda,
+        cityMint: cityMintPda,
+      })
+      .signers([admin])
+      .rpc();
+    await hookProgram.methods
+      .initializeExtraAccountMetaList()
+      .accountsPartial({ payer: admin.publicKey, mint: cityMintPda })
+      .signers([admin])
+      .rpc();
+    console.log("City registered:", cityName);
+
+    // The admin is also the compliance authority until one is set
+    const kycExpiry = new anchor.BN(Math.floor(Date.now() / 1000) + 365 * 86_400);
+    for (const wallet of [user.publicKey, admin.publicKey]) {
+      await program.methods
+        .issueKyc(Array.from(Buffer.from("US")), kycExpiry)
+        .accountsPartial({ complianceAuthority: admin.publicKey, wallet })
+        .signers([admin])
+        .rpc();
+    }
+  });
+
+  it("should reject city price updates from a non-admin", async () => {
+    try {
+      await program.methods
+        .setCityPrice(cityName, new anchor.BN(1).mul(microUsd), new anchor.BN(0))
+        .accountsPartial({
+          admin: user.publicKey,
+          cityPrice: cityPricePda,
+        })
+        .rpc();
+      assert.fail("Transaction should have failed for a non-admin signer");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("Unauthorized") ||
+        logs.includes("Unauthorized") ||
+        err.error?.errorCode?.code === "Unauthorized"
+      );
+    }
+  });
+
+  it("should fail to buy a city that was not registered", async () => {
+    const unknownCity = "Atlantis";
+    const [cityMintPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("city-mint"), Buffer.from(unknownCity)],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .buy(unknownCity, new anchor.BN(1_000_000_000), new anchor.BN(0), null)
+        .accountsPartial({
+          user: user.publicKey,
+          vault: vaultPda,
+          solUsdPrice: solUsdFeed,
+          cityMint: cityMintPda,
+          userAta: await associatedAddress({ mint: cityMintPda, owner: user.publicKey }),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Transaction should have failed for an unregistered city");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("CityNotFound") ||
+        logs.includes("CityNotFound") ||
+        err.error?.errorCode?.code === "CityNotFound"
+      );
+    }
   });
 
   it("should successfully buy tokens using SOL units", async () => {
     const lamports = new anchor.BN(1_000_000_000); // 1 SOL
     const circleRate = new anchor.BN(10); // $10 per city token to get >0 tokens
+    await setCityPrice(circleRate);
 
     const [cityConfigPda] = await PublicKey.findProgramAddress(
       [Buffer.from("city-config"), Buffer.from(cityName)],
@@ -68,18 +281,18 @@ describe("buy_token tests (SOL-based) - FIXED", () => {
     const userAta = await associatedAddress({ mint: cityMintPda, owner: user.publicKey });
 
     const txSig = await program.methods
-      .buy(cityName, circleRate, lamports, solPriceUsd)
+      .buy(cityName, lamports, new anchor.BN(0), null)
       .accountsPartial({
         user: user.publicKey,
-        admin: admin.publicKey,
         vault: vaultPda,
+        solUsdPrice: solUsdFeed,
+        cityPrice: cityPricePda,
         cityConfig: cityConfigPda,
         cityMint: cityMintPda,
         userAta,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       })
       .rpc();
     console.log("Buy transaction signature:", txSig);
@@ -103,9 +316,24 @@ describe("buy_token tests (SOL-based) - FIXED", () => {
     assert.equal(tokenAccount.amount.toString(), expectedAmount.toString());
   });
 
-  it("should fail if circle_rate is 0", async () => {
+  it("should fail if city price is 0", async () => {
+    try {
+      await setCityPrice(new anchor.BN(0));
+      assert.fail("Transaction should have failed due to zero price");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("InvalidPrice") ||
+        logs.includes("InvalidPrice") ||
+        err.error?.errorCode?.code === "InvalidPrice"
+      );
+    }
+  });
+
+  it("should fail if the SOL/USD price is stale", async () => {
     const lamports = new anchor.BN(1_000_000_000);
-    const circleRate = new anchor.BN(0);
+    await configureOracle(new anchor.BN(60));
 
     const [cityConfigPda] = await PublicKey.findProgramAddress(
       [Buffer.from("city-config"), Buffer.from(cityName)],
@@ -119,35 +347,199 @@ describe("buy_token tests (SOL-based) - FIXED", () => {
 
     try {
       await program.methods
-        .buy(cityName, circleRate, lamports, solPriceUsd)
+        .buy(cityName, lamports, new anchor.BN(0), null)
         .accountsPartial({
           user: user.publicKey,
-          admin: admin.publicKey,
           vault: vaultPda,
+          solUsdPrice: solUsdFeed,
+          cityPrice: cityPricePda,
           cityConfig: cityConfigPda,
           cityMint: cityMintPda,
           userAta,
-          tokenProgram: TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: anchor.web3.SystemProgram.programId,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         })
         .rpc();
-      assert.fail("Transaction should have failed due to zero rate");
+      assert.fail("Transaction should have failed due to stale price");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("StalePrice") ||
+        logs.includes("StalePrice") ||
+        err.error?.errorCode?.code === "StalePrice"
+      );
+    } finally {
+      await configureOracle(testMaxPriceAge);
+    }
+  });
+
+  it("should accept city prices for a week by default", async () => {
+    assert.equal(initialMaxCityPriceAge.toString(), defaultMaxCityPriceAge.toString());
+
+    // Only the SOL/USD age is relaxed for the fixture
+    const [protocolConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("protocol-config")],
+      program.programId
+    );
+    const config = await program.account.protocolConfig.fetch(protocolConfigPda);
+    assert.equal(config.maxCityPriceAge.toString(), defaultMaxCityPriceAge.toString());
+    assert.equal(config.maxPriceAge.toString(), testMaxPriceAge.toString());
+  });
+
+  it("should fail if the city price is older than the city price age limit", async () => {
+    await setCityPrice(new anchor.BN(10));
+    await configureOracle(testMaxPriceAge, new anchor.BN(1));
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+    try {
+      await program.methods
+        .buy(cityName, new anchor.BN(100_000_000), new anchor.BN(0), null)
+        .accountsPartial({
+          user: user.publicKey,
+          vault: vaultPda,
+          solUsdPrice: solUsdFeed,
+          cityMint: cityMintPda(),
+          userAta: userCityAta(),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Transaction should have failed due to a stale city price");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("StalePrice") ||
+        logs.includes("StalePrice") ||
+        err.error?.errorCode?.code === "StalePrice"
+      );
+    } finally {
+      await configureOracle(testMaxPriceAge);
+    }
+  });
+
+  it("should fail to buy when fewer tokens than min_tokens_out would be minted", async () => {
+    const circleRate = new anchor.BN(10);
+    await setCityPrice(circleRate);
+
+    const [cityMintPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("city-mint"), Buffer.from(cityName)],
+      program.programId
+    );
+    // 1 SOL at $200 and $10 per token mints 20 tokens, ask for one more
+    const minTokensOut = new anchor.BN(21_000_000);
+
+    try {
+      await program.methods
+        .buy(cityName, new anchor.BN(1_000_000_000), minTokensOut, null)
+        .accountsPartial({
+          user: user.publicKey,
+          vault: vaultPda,
+          solUsdPrice: solUsdFeed,
+          cityMint: cityMintPda,
+          userAta: await associatedAddress({ mint: cityMintPda, owner: user.publicKey }),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Transaction should have failed due to slippage");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("SlippageExceeded") ||
+        logs.includes("SlippageExceeded") ||
+        err.error?.errorCode?.code === "SlippageExceeded"
+      );
+    }
+  });
+
+  it("should fail to buy while trading is paused", async () => {
+    const tradingFlag = 1;
+    const [cityMintPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("city-mint"), Buffer.from(cityName)],
+      program.programId
+    );
+
+    // The admin is also the guardian until one is set
+    await program.methods
+      .pause(tradingFlag)
+      .accountsPartial({ guardian: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+    try {
+      await program.methods
+        .buy(cityName, new anchor.BN(1_000_000_000), new anchor.BN(0), null)
+        .accountsPartial({
+          user: user.publicKey,
+          vault: vaultPda,
+          solUsdPrice: solUsdFeed,
+          cityMint: cityMintPda,
+          userAta: await associatedAddress({ mint: cityMintPda, owner: user.publicKey }),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Transaction should have failed while trading is paused");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("TradingPaused") ||
+        logs.includes("TradingPaused") ||
+        err.error?.errorCode?.code === "TradingPaused"
+      );
+    } finally {
+      await program.methods
+        .unpause(tradingFlag)
+        .accountsPartial({ admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    }
+  });
+
+  it("should fail to buy while the city is BuyPaused", async () => {
+    const [cityMintPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("city-mint"), Buffer.from(cityName)],
+      program.programId
+    );
+    const setStatus = (status: any) =>
+      program.methods
+        .setCityStatus(cityName, status)
+        .accountsPartial({ admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+    await setStatus({ buyPaused: {} });
+    try {
+      await program.methods
+        .buy(cityName, new anchor.BN(1_000_000_000), new anchor.BN(0), null)
+        .accountsPartial({
+          user: user.publicKey,
+          vault: vaultPda,
+          solUsdPrice: solUsdFeed,
+          cityMint: cityMintPda,
+          userAta: await associatedAddress({ mint: cityMintPda, owner: user.publicKey }),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Transaction should have failed while buys are paused");
     } catch (err: any) {
       const errorString = err.toString();
       const logs = err.logs?.join("\n") || "";
       assert.ok(
-        errorString.includes("RateNotValid") || 
-        logs.includes("RateNotValid") ||
-        err.error?.errorCode?.code === "RateNotValid"
+        errorString.includes("CityBuyDisabled") ||
+        logs.includes("CityBuyDisabled") ||
+        err.error?.errorCode?.code === "CityBuyDisabled"
       );
+    } finally {
+      await setStatus({ active: {} });
     }
   });
 
   it("should mint to existing ATA if already exists", async () => {
     const lamports = new anchor.BN(1_000_000_000);
     const circleRate = new anchor.BN(5); // $5 per token
+    await setCityPrice(circleRate);
 
     const [cityConfigPda] = await PublicKey.findProgramAddress(
       [Buffer.from("city-config"), Buffer.from(cityName)],
@@ -166,18 +558,18 @@ describe("buy_token tests (SOL-based) - FIXED", () => {
     } catch {}
 
     await program.methods
-      .buy(cityName, circleRate, lamports, solPriceUsd)
+      .buy(cityName, lamports, new anchor.BN(0), null)
       .accountsPartial({
         user: user.publicKey,
-        admin: admin.publicKey,
         vault: vaultPda,
+        solUsdPrice: solUsdFeed,
+        cityPrice: cityPricePda,
         cityConfig: cityConfigPda,
         cityMint: cityMintPda,
         userAta,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       })
       .rpc();
 
@@ -193,4 +585,1308 @@ describe("buy_token tests (SOL-based) - FIXED", () => {
 
     assert.equal(tokenAccount.amount.toString(), expectedTotal.toString());
   });
+
+  it("should charge the city buy fee into the fee treasury", async () => {
+    const lamports = new anchor.BN(1_000_000_000);
+    const circleRate = new anchor.BN(10);
+    const buyFeeBps = 100; // 1%
+    await setCityPrice(circleRate);
+
+    const [cityMintPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("city-mint"), Buffer.from(cityName)],
+      program.programId
+    );
+    const [feeTreasuryPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("fee-treasury")],
+      program.programId
+    );
+    const userAta = await associatedAddress({ mint: cityMintPda, owner: user.publicKey });
+
+    await program.methods
+      .setCityFees(cityName, buyFeeBps, null)
+      .accountsPartial({ admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+    const treasuryBefore = await program.account.feeTreasury.fetch(feeTreasuryPda);
+    const balanceBefore = new anchor.BN(
+      (await getAccount(provider.connection, userAta)).amount.toString()
+    );
+
+    try {
+      await program.methods
+        .buy(cityName, lamports, new anchor.BN(0), null)
+        .accountsPartial({
+          user: user.publicKey,
+          vault: vaultPda,
+          solUsdPrice: solUsdFeed,
+          cityMint: cityMintPda,
+          userAta,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+    } finally {
+      await program.methods
+        .setCityFees(cityName, null, null)
+        .accountsPartial({ admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    }
+
+    const fee = lamports.muln(buyFeeBps).divn(10_000);
+    const treasuryAfter = await program.account.feeTreasury.fetch(feeTreasuryPda);
+    assert.equal(
+      treasuryAfter.balance.sub(treasuryBefore.balance).toString(),
+      fee.toString()
+    );
+
+    // Tokens are minted on the lamports left after the fee
+    const expectedIncrease = lamports
+      .sub(fee)
+      .mul(solPriceUsd)
+      .mul(new anchor.BN(1_000_000))
+      .div(circleRate)
+      .div(new anchor.BN(1_000_000_000));
+    const tokenAccount = await getAccount(provider.connection, userAta);
+    assert.equal(
+      tokenAccount.amount.toString(),
+      balanceBefore.add(expectedIncrease).toString()
+    );
+  });
+
+  it("should mint exactly the requested amount with buy_exact_out", async () => {
+    const circleRate = new anchor.BN(10);
+    await setCityPrice(circleRate);
+
+    const [cityMintPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("city-mint"), Buffer.from(cityName)],
+      program.programId
+    );
+    const userAta = await associatedAddress({ mint: cityMintPda, owner: user.publicKey });
+    const balanceBefore = new anchor.BN(
+      (await getAccount(provider.connection, userAta)).amount.toString()
+    );
+
+    // 7 tokens at $10 with SOL at $200 cost 0.35 SOL
+    const tokenAmount = new anchor.BN(7_000_000);
+    const maxLamportsIn = new anchor.BN(350_000_000);
+
+    await program.methods
+      .buyExactOut(cityName, tokenAmount, maxLamportsIn, null)
+      .accountsPartial({
+        user: user.publicKey,
+        vault: vaultPda,
+        solUsdPrice: solUsdFeed,
+        cityMint: cityMintPda,
+        userAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const tokenAccount = await getAccount(provider.connection, userAta);
+    assert.equal(
+      tokenAccount.amount.toString(),
+      balanceBefore.add(tokenAmount).toString()
+    );
+  });
+
+  it("should refuse withdrawals that break the minimum reserve ratio", async () => {
+    // Buys alone back the vault at exactly 100%, the default minimum
+    const ratio = await program.methods
+      .vaultHealth()
+      .accountsPartial({ vault: vaultPda })
+      .view();
+    assert.equal(ratio.toString(), "10000");
+
+    try {
+      await program.methods
+        .withdraw(new anchor.BN(1))
+        .accountsPartial({
+          admin: admin.publicKey,
+          vault: vaultPda,
+          recipient: anchor.web3.Keypair.generate().publicKey,
+        })
+        .signers([admin])
+        .rpc();
+      assert.fail("Transaction should have failed due to the reserve ratio");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("ReserveRatioTooLow") ||
+        logs.includes("ReserveRatioTooLow") ||
+        err.error?.errorCode?.code === "ReserveRatioTooLow"
+      );
+    }
+  });
+
+  it("should reject a reserve ratio outside the allowed range", async () => {
+    for (const ratio of [0, 4_999, 20_001]) {
+      try {
+        await program.methods
+          .updateProtocolConfig(0, 0, ratio, 0b111)
+          .accountsPartial({ admin: admin.publicKey })
+          .signers([admin])
+          .rpc();
+        assert.fail(`Transaction should have failed for a ${ratio} bps reserve ratio`);
+      } catch (err: any) {
+        const errorString = err.toString();
+        const logs = err.logs?.join("\n") || "";
+        assert.ok(
+          errorString.includes("ReserveRatioOutOfRange") ||
+          logs.includes("ReserveRatioOutOfRange") ||
+          err.error?.errorCode?.code === "ReserveRatioOutOfRange"
+        );
+      }
+    }
+  });
+
+  it("should reject unknown feature bits", async () => {
+    try {
+      await program.methods
+        .updateProtocolConfig(0, 0, 10_000, 0b1000)
+        .accountsPartial({ admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+      assert.fail("Transaction should have failed for an unknown feature bit");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("InvalidPauseFlags") ||
+        logs.includes("InvalidPauseFlags") ||
+        err.error?.errorCode?.code === "InvalidPauseFlags"
+      );
+    }
+  });
+
+  it("should keep the vault balance equal to lamports above rent after a withdrawal", async () => {
+    const recipient = anchor.web3.Keypair.generate().publicKey;
+    const amount = new anchor.BN(100_000_000);
+
+    // Allow the vault to run at a 50% reserve
+    await program.methods
+      .updateProtocolConfig(0, 0, 5_000, 0b111)
+      .accountsPartial({ admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+    await program.methods
+      .withdraw(amount)
+      .accountsPartial({
+        admin: admin.publicKey,
+        vault: vaultPda,
+        recipient,
+      })
+      .signers([admin])
+      .rpc();
+
+    const vaultInfo = await provider.connection.getAccountInfo(vaultPda);
+    const rentExempt = await provider.connection.getMinimumBalanceForRentExemption(
+      vaultInfo!.data.length
+    );
+    const vaultAccount = await program.account.vault.fetch(vaultPda);
+    assert.equal(
+      vaultAccount.balance.toString(),
+      (vaultInfo!.lamports - rentExempt).toString()
+    );
+    assert.equal(await provider.connection.getBalance(recipient), amount.toNumber());
+  });
+
+  const sellTokens = (tokenAmount: anchor.BN, minLamportsOut: anchor.BN, deadline: anchor.BN | null) =>
+    program.methods
+      .sell(cityName, tokenAmount, minLamportsOut, deadline)
+      .accountsPartial({
+        user: user.publicKey,
+        vault: vaultPda,
+        solUsdPrice: solUsdFeed,
+        cityMint: cityMintPda(),
+        userAta: userCityAta(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+  it("should pay sellers from the vault and charge the sell fee", async () => {
+    const tokenAmount = new anchor.BN(1_000_000);
+    const sellFeeBps = 100; // 1%
+    const [feeTreasuryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fee-treasury")],
+      program.programId
+    );
+    await setCityPrice(new anchor.BN(10));
+    await program.methods
+      .setCityFees(cityName, null, sellFeeBps)
+      .accountsPartial({ admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+    const treasuryBefore = await program.account.feeTreasury.fetch(feeTreasuryPda);
+    const vaultBefore = await program.account.vault.fetch(vaultPda);
+    const lamportsBefore = await provider.connection.getBalance(user.publicKey);
+    const tokensBefore = (await getAccount(provider.connection, userCityAta())).amount;
+
+    let txFee: number;
+    try {
+      const sig = await sellTokens(tokenAmount, new anchor.BN(0), null);
+      await provider.connection.confirmTransaction(sig, "confirmed");
+      const tx = await provider.connection.getTransaction(sig, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      txFee = tx!.meta!.fee;
+    } finally {
+      await program.methods
+        .setCityFees(cityName, null, null)
+        .accountsPartial({ admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    }
+
+    // 1 token at $10 with SOL at $200 is 0.05 SOL, less the rounded-up fee
+    const gross = tokenAmount.mul(new anchor.BN(10)).mul(new anchor.BN(1_000_000_000)).div(
+      solPriceUsd.mul(new anchor.BN(1_000_000))
+    );
+    const fee = gross.muln(sellFeeBps).addn(9_999).divn(10_000);
+    const lamportsAfter = await provider.connection.getBalance(user.publicKey);
+    assert.equal(lamportsAfter - lamportsBefore + txFee, gross.sub(fee).toNumber());
+
+    const tokensAfter = (await getAccount(provider.connection, userCityAta())).amount;
+    assert.equal((tokensBefore - tokensAfter).toString(), tokenAmount.toString());
+
+    const treasuryAfter = await program.account.feeTreasury.fetch(feeTreasuryPda);
+    assert.equal(treasuryAfter.balance.sub(treasuryBefore.balance).toString(), fee.toString());
+
+    // The vault pays both the seller and the fee, and its balance stays synced
+    const vaultAfter = await program.account.vault.fetch(vaultPda);
+    assert.equal(vaultBefore.balance.sub(vaultAfter.balance).toString(), gross.toString());
+    const vaultInfo = await provider.connection.getAccountInfo(vaultPda);
+    const rentExempt = await provider.connection.getMinimumBalanceForRentExemption(
+      vaultInfo!.data.length
+    );
+    assert.equal(vaultAfter.balance.toString(), (vaultInfo!.lamports - rentExempt).toString());
+  });
+
+  it("should reject a sell that returns less than min_lamports_out", async () => {
+    try {
+      await sellTokens(new anchor.BN(1_000_000), new anchor.BN(1_000_000_000), null);
+      assert.fail("Transaction should have failed due to slippage");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("SlippageExceeded") ||
+        logs.includes("SlippageExceeded") ||
+        err.error?.errorCode?.code === "SlippageExceeded"
+      );
+    }
+  });
+
+  it("should reject a sell after its deadline", async () => {
+    const deadline = new anchor.BN(Math.floor(Date.now() / 1000) - 3_600);
+    try {
+      await sellTokens(new anchor.BN(1_000_000), new anchor.BN(0), deadline);
+      assert.fail("Transaction should have failed after the deadline");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("DeadlineExceeded") ||
+        logs.includes("DeadlineExceeded") ||
+        err.error?.errorCode?.code === "DeadlineExceeded"
+      );
+    }
+  });
+
+  it("should accrue at the old rate before a rate change and the new rate after", async () => {
+    const rateBps = 5_000;
+    // A stake large enough to earn whole base units within a few seconds
+    await program.methods
+      .buy(cityName, new anchor.BN(10_000_000_000), new anchor.BN(0), null)
+      .accountsPartial({
+        user: user.publicKey,
+        vault: vaultPda,
+        solUsdPrice: solUsdFeed,
+        cityMint: cityMintPda(),
+        userAta: userCityAta(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const poolBefore = await program.account.stakePool.fetch(stakePoolPda());
+    await new Promise((resolve) => setTimeout(resolve, 1_000));
+    await program.methods
+      .setRewardRate(cityName, rateBps)
+      .accountsPartial({ admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+    // Time up to the change is closed out at the default 0% rate
+    const poolAtChange = await program.account.stakePool.fetch(stakePoolPda());
+    assert.ok(poolAtChange.lastRateChange.gt(poolBefore.lastUpdate));
+    assert.equal(poolAtChange.lastUpdate.toString(), poolAtChange.lastRateChange.toString());
+    assert.equal(
+      poolAtChange.accRewardPerToken.toString(),
+      poolBefore.accRewardPerToken.toString()
+    );
+
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+    await program.methods
+      .openPosition(new anchor.BN(0), new anchor.BN(100_000_000), { flexible: {} })
+      .accountsPartial({
+        user: user.publicKey,
+        cityMint: cityMintPda(),
+        userCityAta: userCityAta(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts(user.publicKey, positionPda(0)))
+      .rpc();
+
+    // Time since the change accrues at the new rate
+    const pool = await program.account.stakePool.fetch(stakePoolPda());
+    const elapsed = pool.lastUpdate.sub(poolAtChange.lastRateChange);
+    const expected = elapsed
+      .muln(rateBps)
+      .mul(new anchor.BN(10).pow(new anchor.BN(12)))
+      .div(new anchor.BN(10_000).mul(new anchor.BN(31_536_000)));
+    assert.ok(expected.gtn(0));
+    assert.equal(
+      pool.accRewardPerToken.sub(poolAtChange.accRewardPerToken).toString(),
+      expected.toString()
+    );
+  });
+
+  it("should settle accrued rewards before a stake top-up", async () => {
+    const firstStake = new anchor.BN(100_000_000);
+    const topUp = new anchor.BN(4_000_000);
+    const poolBefore = await program.account.stakePool.fetch(stakePoolPda());
+
+    await new Promise((resolve) => setTimeout(resolve, 3_000));
+    await program.methods
+      .stake(new anchor.BN(0), topUp, { flexible: {} })
+      .accountsPartial({
+        user: user.publicKey,
+        cityMint: cityMintPda(),
+        userCityAta: userCityAta(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts(user.publicKey, positionPda(0)))
+      .rpc();
+
+    const pool = await program.account.stakePool.fetch(stakePoolPda());
+    const position = await program.account.userStake.fetch(positionPda(0));
+    assert.equal(
+      pool.totalStaked.sub(poolBefore.totalStaked).toString(),
+      topUp.toString()
+    );
+    assert.equal(position.stakedAmount.toString(), firstStake.add(topUp).toString());
+
+    // Only the first stake earned, over the time before the top-up
+    const accrued = firstStake
+      .mul(pool.accRewardPerToken.sub(poolBefore.accRewardPerToken))
+      .div(new anchor.BN(10).pow(new anchor.BN(12)));
+    assert.ok(position.pendingRewards.gtn(0));
+    assert.equal(position.pendingRewards.toString(), accrued.toString());
+    assert.equal(
+      position.rewardDebt.toString(),
+      position.stakedAmount.mul(pool.accRewardPerToken).toString()
+    );
+  });
+
+  it("should refuse to switch the reward mint while stakers are earning", async () => {
+    try {
+      await program.methods
+        .setRewardMint(cityName)
+        .accountsPartial({ admin: admin.publicKey, rewardMint: NATIVE_MINT })
+        .signers([admin])
+        .rpc();
+      assert.fail("Transaction should have failed with stake in the pool");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("RewardsOutstanding") ||
+        logs.includes("RewardsOutstanding") ||
+        err.error?.errorCode?.code === "RewardsOutstanding"
+      );
+    }
+  });
+
+  it("should split a position and merge it back", async () => {
+    const staked = (await program.account.userStake.fetch(positionPda(0))).stakedAmount;
+    await program.methods
+      .splitPosition(new anchor.BN(0), new anchor.BN(2), new anchor.BN(2_000_000))
+      .accountsPartial({
+        user: user.publicKey,
+        cityMint: cityMintPda(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts(positionPda(0), positionPda(2)))
+      .rpc();
+
+    let source = await program.account.userStake.fetch(positionPda(0));
+    const split = await program.account.userStake.fetch(positionPda(2));
+    assert.equal(source.stakedAmount.toString(), staked.subn(2_000_000).toString());
+    assert.equal(split.stakedAmount.toString(), "2000000");
+    assert.equal(split.lockEnd.toString(), source.lockEnd.toString());
+
+    await program.methods
+      .mergePositions(new anchor.BN(2), new anchor.BN(0))
+      .accountsPartial({
+        user: user.publicKey,
+        cityMint: cityMintPda(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts(positionPda(2), positionPda(0)))
+      .rpc();
+
+    source = await program.account.userStake.fetch(positionPda(0));
+    assert.equal(source.stakedAmount.toString(), staked.toString());
+    assert.equal(await provider.connection.getAccountInfo(positionPda(2)), null);
+  });
+
+  it("should lock a merged position for the full term of its stricter tier", async () => {
+    const positionAccounts = {
+      user: user.publicKey,
+      cityMint: cityMintPda(),
+      userCityAta: userCityAta(),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+    await program.methods
+      .openPosition(new anchor.BN(6), new anchor.BN(2_000_000), { flexible: {} })
+      .accountsPartial(positionAccounts)
+      .remainingAccounts(hookAccounts(user.publicKey, positionPda(6)))
+      .rpc();
+    await program.methods
+      .openPosition(new anchor.BN(5), new anchor.BN(1_000_000), { days30: {} })
+      .accountsPartial(positionAccounts)
+      .remainingAccounts(hookAccounts(user.publicKey, positionPda(5)))
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 1_000));
+
+    await program.methods
+      .mergePositions(new anchor.BN(5), new anchor.BN(6))
+      .accountsPartial({
+        user: user.publicKey,
+        cityMint: cityMintPda(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts(positionPda(5), positionPda(6)))
+      .rpc();
+
+    // The boost now covers 3 tokens, so all of them are locked from the merge on
+    const pool = await program.account.stakePool.fetch(stakePoolPda());
+    const merged = await program.account.userStake.fetch(positionPda(6));
+    assert.equal(merged.stakedAmount.toString(), "3000000");
+    assert.deepEqual(merged.lockTier, { days30: {} });
+    assert.equal(merged.rewardMultiplierBps, 11_000);
+    assert.equal(merged.lockEnd.toString(), pool.lastUpdate.addn(30 * 86_400).toString());
+  });
+
+  it("should reject a second reward rate change inside the cooldown", async () => {
+    const setRate = (rateBps: number) =>
+      program.methods
+        .setRewardRate(cityName, rateBps)
+        .accountsPartial({ admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+    // The rate was already changed once today
+    try {
+      await setRate(1_000);
+      assert.fail("Transaction should have failed inside the cooldown");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("RewardRateChangeTooSoon") ||
+        logs.includes("RewardRateChangeTooSoon") ||
+        err.error?.errorCode?.code === "RewardRateChangeTooSoon"
+      );
+    }
+  });
+
+  it("should reject unstaking a locked position without an early-exit penalty", async () => {
+    await program.methods
+      .openPosition(new anchor.BN(1), new anchor.BN(1_000_000), { days30: {} })
+      .accountsPartial({
+        user: user.publicKey,
+        cityMint: cityMintPda(),
+        userCityAta: userCityAta(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts(user.publicKey, positionPda(1)))
+      .rpc();
+
+    try {
+      await program.methods
+        .requestUnstake(new anchor.BN(1), new anchor.BN(1_000_000))
+        .accountsPartial({
+          user: user.publicKey,
+          cityMint: cityMintPda(),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Transaction should have failed for a locked stake");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("StakeLocked") ||
+        logs.includes("StakeLocked") ||
+        err.error?.errorCode?.code === "StakeLocked"
+      );
+    }
+  });
+
+  it("should fund the reward pool from the admin's token account", async () => {
+    const adminCityAta = associatedAddress({ mint: cityMintPda(), owner: admin.publicKey });
+    await program.methods
+      .buy(cityName, new anchor.BN(100_000_000), new anchor.BN(0), null)
+      .accountsPartial({
+        user: admin.publicKey,
+        vault: vaultPda,
+        solUsdPrice: solUsdFeed,
+        cityMint: cityMintPda(),
+        userAta: adminCityAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc();
+
+    // Rewards are paid in the city token until another mint is set
+    await program.methods
+      .fundRewards(cityName, new anchor.BN(1_000_000))
+      .accountsPartial({
+        admin: admin.publicKey,
+        rewardMint: cityMintPda(),
+        adminRewardAta: adminCityAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts(admin.publicKey, stakePoolPda()))
+      .signers([admin])
+      .rpc();
+
+    const pool = await program.account.stakePool.fetch(stakePoolPda());
+    assert.equal(pool.rewardBalance.toString(), "1000000");
+    const poolAta = await getAccount(
+      provider.connection,
+      associatedAddress({ mint: cityMintPda(), owner: stakePoolPda() })
+    );
+    assert.equal(poolAta.amount.toString(), "1000000");
+  });
+
+  it("should only sweep penalties the pool has recorded", async () => {
+    // City-token pools fold penalties into rewards, so there is nothing to sweep
+    try {
+      await program.methods
+        .sweepPenalties(cityName, new anchor.BN(1))
+        .accountsPartial({
+          admin: admin.publicKey,
+          cityMint: cityMintPda(),
+          adminCityAta: associatedAddress({ mint: cityMintPda(), owner: admin.publicKey }),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .remainingAccounts(hookAccounts(stakePoolPda(), admin.publicKey))
+        .signers([admin])
+        .rpc();
+      assert.fail("Transaction should have failed without penalties");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("InsufficientPenaltyBalance") ||
+        logs.includes("InsufficientPenaltyBalance") ||
+        err.error?.errorCode?.code === "InsufficientPenaltyBalance"
+      );
+    }
+  });
+
+  it("should drop the lock boost of a position whose lock has ended", async () => {
+    // A full early exit ends the lock, so cancelling it restakes on flexible terms
+    await program.methods
+      .setEarlyExitPenalty(cityName, 1_000)
+      .accountsPartial({ admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+    try {
+      await program.methods
+        .requestUnstake(new anchor.BN(1), new anchor.BN(1_000_000))
+        .accountsPartial({
+          user: user.publicKey,
+          cityMint: cityMintPda(),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .remainingAccounts(hookAccounts(positionPda(1), stakePoolPda()))
+        .rpc();
+    } finally {
+      await program.methods
+        .setEarlyExitPenalty(cityName, null)
+        .accountsPartial({ admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    }
+    let position = await program.account.userStake.fetch(positionPda(1));
+    assert.equal(position.lockEnd.toString(), "0");
+    assert.equal(position.rewardMultiplierBps, 11_000);
+
+    await program.methods
+      .cancelUnstake(new anchor.BN(1))
+      .accountsPartial({ user: user.publicKey, cityMint: cityMintPda() })
+      .rpc();
+
+    position = await program.account.userStake.fetch(positionPda(1));
+    assert.equal(position.stakedAmount.toString(), "900000");
+    assert.deepEqual(position.lockTier, { flexible: {} });
+    assert.equal(position.rewardMultiplierBps, 10_000);
+    assert.equal(position.weight.toString(), position.stakedAmount.toString());
+  });
+
+  it("should reject compounding a position with no accrued rewards", async () => {
+    try {
+      await program.methods
+        .compound(new anchor.BN(1))
+        .accountsPartial(compoundAccounts())
+        .remainingAccounts(hookAccounts(stakePoolPda(), positionPda(1)))
+        .rpc();
+      assert.fail("Transaction should have failed without rewards");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("NoRewardsAvailable") ||
+        logs.includes("NoRewardsAvailable") ||
+        err.error?.errorCode?.code === "NoRewardsAvailable"
+      );
+    }
+  });
+
+  it("should compound city-token rewards by restaking them", async () => {
+    const adminCityAta = associatedAddress({ mint: cityMintPda(), owner: admin.publicKey });
+    const accounts = compoundAccounts();
+    await program.methods
+      .depositYield(cityName, new anchor.BN(500_000))
+      .accountsPartial({
+        admin: admin.publicKey,
+        rewardMint: cityMintPda(),
+        adminRewardAta: adminCityAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts(admin.publicKey, stakePoolPda()))
+      .signers([admin])
+      .rpc();
+
+    const stakeVaultAta = associatedAddress({ mint: cityMintPda(), owner: positionPda(0) });
+    const positionBefore = await program.account.userStake.fetch(positionPda(0));
+    const poolBefore = await program.account.stakePool.fetch(stakePoolPda());
+    const cityBefore = await program.account.cityConfig.fetch(accounts.cityConfig);
+    const vaultBefore = await program.account.vault.fetch(vaultPda);
+    const adminBefore = (await getAccount(provider.connection, adminCityAta)).amount;
+    const stakeVaultBefore = (await getAccount(provider.connection, stakeVaultAta)).amount;
+
+    await program.methods
+      .compound(new anchor.BN(0))
+      .accountsPartial(accounts)
+      .remainingAccounts(hookAccounts(stakePoolPda(), positionPda(0)))
+      .rpc();
+
+    const position = await program.account.userStake.fetch(positionPda(0));
+    const pool = await program.account.stakePool.fetch(stakePoolPda());
+    const city = await program.account.cityConfig.fetch(accounts.cityConfig);
+    const vault = await program.account.vault.fetch(vaultPda);
+    const restaked = position.stakedAmount.sub(positionBefore.stakedAmount);
+    assert.ok(restaked.gtn(0));
+
+    // The reward tokens themselves move from the pool into the position
+    const paid = poolBefore.rewardBalance.sub(pool.rewardBalance);
+    assert.equal(paid.toString(), restaked.toString());
+    const stakeVaultAfter = (await getAccount(provider.connection, stakeVaultAta)).amount;
+    assert.equal((stakeVaultAfter - stakeVaultBefore).toString(), restaked.toString());
+    assert.equal(pool.totalStaked.sub(poolBefore.totalStaked).toString(), restaked.toString());
+
+    // Nothing is minted, nothing reaches the admin and the vault's backing is untouched
+    const adminAfter = (await getAccount(provider.connection, adminCityAta)).amount;
+    assert.equal(adminAfter.toString(), adminBefore.toString());
+    assert.equal(city.totalSupply.toString(), cityBefore.totalSupply.toString());
+    assert.equal(vault.liabilities.toString(), vaultBefore.liabilities.toString());
+    assert.equal(vault.balance.toString(), vaultBefore.balance.toString());
+  });
+
+  it("should reject closing a position that still holds stake", async () => {
+    try {
+      await program.methods
+        .closeStake(new anchor.BN(0))
+        .accountsPartial({
+          user: user.publicKey,
+          cityMint: cityMintPda(),
+          userCityAta: userCityAta(),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Transaction should have failed for a non-empty position");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("PositionNotEmpty") ||
+        logs.includes("PositionNotEmpty") ||
+        err.error?.errorCode?.code === "PositionNotEmpty"
+      );
+    }
+  });
+
+  it("should distribute deposited yield through the pool index", async () => {
+    const adminCityAta = associatedAddress({ mint: cityMintPda(), owner: admin.publicKey });
+    const before = await program.account.stakePool.fetch(stakePoolPda());
+
+    await program.methods
+      .depositYield(cityName, new anchor.BN(500_000))
+      .accountsPartial({
+        admin: admin.publicKey,
+        rewardMint: cityMintPda(),
+        adminRewardAta: adminCityAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts(admin.publicKey, stakePoolPda()))
+      .signers([admin])
+      .rpc();
+
+    const after = await program.account.stakePool.fetch(stakePoolPda());
+    assert.equal(
+      after.rewardBalance.sub(before.rewardBalance).toString(),
+      "500000"
+    );
+    // Split by staked tokens, not by lock-boosted weight
+    const perToken = new anchor.BN(500_000)
+      .mul(new anchor.BN(10).pow(new anchor.BN(12)))
+      .div(after.totalStaked);
+    assert.equal(
+      after.accYieldPerToken.sub(before.accYieldPerToken).toString(),
+      perToken.toString()
+    );
+  });
+
+  it("should pay claimed rewards from the pool's token account", async () => {
+    const poolBefore = await program.account.stakePool.fetch(stakePoolPda());
+    const balanceBefore = (await getAccount(provider.connection, userCityAta())).amount;
+
+    await program.methods
+      .claim(new anchor.BN(0))
+      .accountsPartial({
+        user: user.publicKey,
+        cityMint: cityMintPda(),
+        rewardMint: cityMintPda(),
+        userRewardAta: userCityAta(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts(stakePoolPda(), user.publicKey))
+      .rpc();
+
+    const poolAfter = await program.account.stakePool.fetch(stakePoolPda());
+    const position = await program.account.userStake.fetch(positionPda(0));
+    const paid = poolBefore.rewardBalance.sub(poolAfter.rewardBalance);
+    assert.ok(paid.gtn(0));
+    assert.equal(position.pendingRewards.toString(), "0");
+    const balanceAfter = (await getAccount(provider.connection, userCityAta())).amount;
+    assert.equal((balanceAfter - balanceBefore).toString(), paid.toString());
+    assert.equal(
+      poolBefore.unclaimedRewards.sub(poolAfter.unclaimedRewards).toString(),
+      paid.toString()
+    );
+  });
+
+  it("should hold unstaked tokens through the cooldown until cancelled", async () => {
+    const staked = (await program.account.userStake.fetch(positionPda(0))).stakedAmount;
+    await program.methods
+      .requestUnstake(new anchor.BN(0), new anchor.BN(1_000_000))
+      .accountsPartial({
+        user: user.publicKey,
+        cityMint: cityMintPda(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    let position = await program.account.userStake.fetch(positionPda(0));
+    assert.equal(position.stakedAmount.toString(), staked.subn(1_000_000).toString());
+    assert.equal(position.unbondingAmount.toString(), "1000000");
+
+    try {
+      await program.methods
+        .completeUnstake(new anchor.BN(0))
+        .accountsPartial({
+          user: user.publicKey,
+          cityMint: cityMintPda(),
+          userCityAta: userCityAta(),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Transaction should have failed during the cooldown");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("UnbondingNotFinished") ||
+        logs.includes("UnbondingNotFinished") ||
+        err.error?.errorCode?.code === "UnbondingNotFinished"
+      );
+    }
+
+    await program.methods
+      .cancelUnstake(new anchor.BN(0))
+      .accountsPartial({ user: user.publicKey, cityMint: cityMintPda() })
+      .rpc();
+
+    position = await program.account.userStake.fetch(positionPda(0));
+    assert.equal(position.stakedAmount.toString(), staked.toString());
+    assert.equal(position.unbondingAmount.toString(), "0");
+  });
+
+  it("should release unbonded tokens once the cooldown has passed", async () => {
+    const stakingFlag = 2;
+    const completeUnstake = () =>
+      program.methods
+        .completeUnstake(new anchor.BN(0))
+        .accountsPartial({
+          user: user.publicKey,
+          cityMint: cityMintPda(),
+          userCityAta: userCityAta(),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .remainingAccounts(hookAccounts(positionPda(0), user.publicKey))
+        .rpc();
+
+    await program.methods
+      .setUnbondingPeriod(cityName, new anchor.BN(0))
+      .accountsPartial({ admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+    await program.methods
+      .requestUnstake(new anchor.BN(0), new anchor.BN(1_000_000))
+      .accountsPartial({
+        user: user.publicKey,
+        cityMint: cityMintPda(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    // The guardian's staking pause also holds tokens in the stake vault
+    await program.methods
+      .pause(stakingFlag)
+      .accountsPartial({ guardian: admin.publicKey })
+      .signers([admin])
+      .rpc();
+    try {
+      await completeUnstake();
+      assert.fail("Transaction should have failed while staking is paused");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("StakingPaused") ||
+        logs.includes("StakingPaused") ||
+        err.error?.errorCode?.code === "StakingPaused"
+      );
+    } finally {
+      await program.methods
+        .unpause(stakingFlag)
+        .accountsPartial({ admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    }
+
+    const balanceBefore = (await getAccount(provider.connection, userCityAta())).amount;
+    await completeUnstake();
+
+    const balanceAfter = (await getAccount(provider.connection, userCityAta())).amount;
+    assert.equal((balanceAfter - balanceBefore).toString(), "1000000");
+    const position = await program.account.userStake.fetch(positionPda(0));
+    assert.equal(position.unbondingAmount.toString(), "0");
+  });
+
+  it("should close an emptied position and return its rent", async () => {
+    const positionId = new anchor.BN(4);
+    const position = positionPda(4);
+    const stakeVaultAta = associatedAddress({ mint: cityMintPda(), owner: position });
+    const positionAccounts = {
+      user: user.publicKey,
+      cityMint: cityMintPda(),
+      userCityAta: userCityAta(),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+
+    // The unbonding period is still 0, so the stake can be withdrawn at once
+    await program.methods
+      .openPosition(positionId, new anchor.BN(1_000_000), { flexible: {} })
+      .accountsPartial(positionAccounts)
+      .remainingAccounts(hookAccounts(user.publicKey, position))
+      .rpc();
+    await program.methods
+      .requestUnstake(positionId, new anchor.BN(1_000_000))
+      .accountsPartial({
+        user: user.publicKey,
+        cityMint: cityMintPda(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+    await program.methods
+      .completeUnstake(positionId)
+      .accountsPartial(positionAccounts)
+      .remainingAccounts(hookAccounts(position, user.publicKey))
+      .rpc();
+
+    const rent =
+      (await provider.connection.getAccountInfo(position))!.lamports +
+      (await provider.connection.getAccountInfo(stakeVaultAta))!.lamports;
+    const lamportsBefore = await provider.connection.getBalance(user.publicKey);
+
+    const sig = await program.methods
+      .closeStake(positionId)
+      .accountsPartial(positionAccounts)
+      .remainingAccounts(hookAccounts(position, user.publicKey))
+      .rpc();
+    await provider.connection.confirmTransaction(sig, "confirmed");
+    const tx = await provider.connection.getTransaction(sig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+
+    assert.equal(await provider.connection.getAccountInfo(position), null);
+    assert.equal(await provider.connection.getAccountInfo(stakeVaultAta), null);
+    const lamportsAfter = await provider.connection.getBalance(user.publicKey);
+    assert.equal(lamportsAfter - lamportsBefore + tx!.meta!.fee, rent);
+  });
+
+  it("should report a user's stake at a past slot", async () => {
+    const stakeAt = (owner: PublicKey, slot: number) =>
+      program.methods
+        .stakeAt(new anchor.BN(slot))
+        .accountsPartial({ user: owner, cityMint: cityMintPda() })
+        .view();
+    const slot = await provider.connection.getSlot();
+    const before = await stakeAt(user.publicKey, slot);
+    assert.ok(before.stakedAmount.gtn(0));
+    assert.ok(before.totalStaked.gte(before.stakedAmount));
+
+    // Make sure the unstake lands in a later slot than the snapshot
+    while ((await provider.connection.getSlot()) <= slot) {
+      await new Promise((resolve) => setTimeout(resolve, 200));
+    }
+    await program.methods
+      .requestUnstake(new anchor.BN(0), new anchor.BN(1_000_000))
+      .accountsPartial({
+        user: user.publicKey,
+        cityMint: cityMintPda(),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    // Changes in later slots do not alter an earlier one
+    const snapshot = await stakeAt(user.publicKey, slot);
+    assert.equal(snapshot.stakedAmount.toString(), before.stakedAmount.toString());
+    assert.equal(snapshot.totalStaked.toString(), before.totalStaked.toString());
+    const latest = await stakeAt(user.publicKey, await provider.connection.getSlot());
+    assert.equal(latest.stakedAmount.toString(), before.stakedAmount.subn(1_000_000).toString());
+
+    // A wallet that never staked has no history and reports zero
+    const stranger = await stakeAt(anchor.web3.Keypair.generate().publicKey, slot);
+    assert.equal(stranger.stakedAmount.toString(), "0");
+  });
+
+  it("should reject a buy from a wallet whose KYC was revoked", async () => {
+    const [kycPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("kyc"), user.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .revokeKyc()
+      .accountsPartial({ complianceAuthority: admin.publicKey, kycRecord: kycPda })
+      .signers([admin])
+      .rpc();
+
+    try {
+      await program.methods
+        .buy(cityName, new anchor.BN(1_000_000_000), new anchor.BN(0), null)
+        .accountsPartial({
+          user: user.publicKey,
+          vault: vaultPda,
+          solUsdPrice: solUsdFeed,
+          cityMint: cityMintPda(),
+          userAta: userCityAta(),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Transaction should have failed without valid KYC");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(
+        errorString.includes("KycNotVerified") ||
+        logs.includes("KycNotVerified") ||
+        err.error?.errorCode?.code === "KycNotVerified"
+      );
+    } finally {
+      await program.methods
+        .updateKyc(
+          { verified: {} },
+          Array.from(Buffer.from("US")),
+          new anchor.BN(Math.floor(Date.now() / 1000) + 365 * 86_400)
+        )
+        .accountsPartial({ complianceAuthority: admin.publicKey, kycRecord: kycPda })
+        .signers([admin])
+        .rpc();
+    }
+  });
+
+  it("should freeze and thaw a holder's token account with a reason code", async () => {
+    const holderAta = associatedAddress({ mint: cityMintPda(), owner: admin.publicKey });
+    const freezeRecordPda = (sequence: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("freeze"),
+          holderAta.toBuffer(),
+          new anchor.BN(sequence).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+    const courtOrder = 1;
+
+    await program.methods
+      .freezeHolder(cityName, courtOrder)
+      .accountsPartial({
+        authority: admin.publicKey,
+        vault: vaultPda,
+        holderAta,
+        freezeRecord: freezeRecordPda(0),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc();
+
+    assert.ok((await getAccount(provider.connection, holderAta)).isFrozen);
+    let record = await program.account.freezeRecord.fetch(freezeRecordPda(0));
+    assert.equal(record.sequence.toString(), "0");
+    assert.equal(record.frozen, true);
+    assert.equal(record.reasonCode, courtOrder);
+
+    await program.methods
+      .thawHolder(cityName, 2)
+      .accountsPartial({
+        authority: admin.publicKey,
+        vault: vaultPda,
+        holderAta,
+        freezeRecord: freezeRecordPda(1),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc();
+
+    assert.ok(!(await getAccount(provider.connection, holderAta)).isFrozen);
+    record = await program.account.freezeRecord.fetch(freezeRecordPda(1));
+    assert.equal(record.sequence.toString(), "1");
+    assert.equal(record.frozen, false);
+    assert.equal(record.reasonCode, 2);
+
+    // The freeze is kept alongside the thaw that followed it
+    record = await program.account.freezeRecord.fetch(freezeRecordPda(0));
+    assert.equal(record.frozen, true);
+    assert.equal(record.reasonCode, courtOrder);
+  });
+
+  it("should block a peer-to-peer transfer to a wallet without KYC", async () => {
+    const stranger = anchor.web3.Keypair.generate().publicKey;
+    const strangerAta = associatedAddress({ mint: cityMintPda(), owner: stranger });
+    const transfer = createTransferCheckedInstruction(
+      userCityAta(),
+      cityMintPda(),
+      strangerAta,
+      user.publicKey,
+      1_000_000,
+      6,
+      [],
+      TOKEN_2022_PROGRAM_ID
+    );
+    transfer.keys.push(...hookAccounts(user.publicKey, stranger));
+
+    try {
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          createAssociatedTokenAccountIdempotentInstruction(
+            user.publicKey,
+            strangerAta,
+            stranger,
+            cityMintPda(),
+            TOKEN_2022_PROGRAM_ID
+          ),
+          transfer
+        )
+      );
+      assert.fail("Transaction should have failed without KYC on the receiver");
+    } catch (err: any) {
+      const errorString = err.toString();
+      const logs = err.logs?.join("\n") || "";
+      assert.ok(errorString.includes("KycRequired") || logs.includes("KycRequired"));
+    }
+  });
+
+  it("should apply the city's jurisdiction rules to transfers", async () => {
+    const adminCityAta = associatedAddress({ mint: cityMintPda(), owner: admin.publicKey });
+    const transfer = () => {
+      const ix = createTransferCheckedInstruction(
+        userCityAta(),
+        cityMintPda(),
+        adminCityAta,
+        user.publicKey,
+        1_000_000,
+        6,
+        [],
+        TOKEN_2022_PROGRAM_ID
+      );
+      ix.keys.push(...hookAccounts(user.publicKey, admin.publicKey));
+      return provider.sendAndConfirm(new anchor.web3.Transaction().add(ix));
+    };
+    const setBlocked = (codes: string[]) =>
+      program.methods
+        .setJurisdictionRules(
+          cityName,
+          codes.map((code) => Array.from(Buffer.from(code)))
+        )
+        .accountsPartial({ complianceAuthority: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+    const balanceBefore = (await getAccount(provider.connection, adminCityAta)).amount;
+    await transfer();
+    const balanceAfter = (await getAccount(provider.connection, adminCityAta)).amount;
+    assert.equal((balanceAfter - balanceBefore).toString(), "1000000");
+
+    await setBlocked(["US"]);
+    try {
+      try {
+        await transfer();
+        assert.fail("Transaction should have failed for a blocked jurisdiction");
+      } catch (err: any) {
+        const errorString = err.toString();
+        const logs = err.logs?.join("\n") || "";
+        assert.ok(
+          errorString.includes("JurisdictionBlocked") || logs.includes("JurisdictionBlocked")
+        );
+      }
+
+      // Minting does not run the hook, so buys check the rules themselves
+      try {
+        await program.methods
+          .buy(cityName, new anchor.BN(100_000_000), new anchor.BN(0), null)
+          .accountsPartial({
+            user: user.publicKey,
+            vault: vaultPda,
+            solUsdPrice: solUsdFeed,
+            cityMint: cityMintPda(),
+            userAta: userCityAta(),
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .rpc();
+        assert.fail("Buy should have failed for a blocked jurisdiction");
+      } catch (err: any) {
+        const errorString = err.toString();
+        const logs = err.logs?.join("\n") || "";
+        assert.ok(
+          errorString.includes("JurisdictionBlocked") ||
+          logs.includes("JurisdictionBlocked") ||
+          err.error?.errorCode?.code === "JurisdictionBlocked"
+        );
+      }
+    } finally {
+      await setBlocked([]);
+    }
+  });
+
+  it("should store city metadata in the mint and let the admin update it", async () => {
+    let metadata = await getTokenMetadata(
+      provider.connection,
+      cityMintPda(),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(metadata.name, cityName);
+    assert.equal(metadata.uri, "https://example.com/testcity.json");
+    assert.ok(metadata.updateAuthority.equals(vaultPda));
+
+    await program.methods
+      .setCityMetadataField(cityName, { propertyAddress: {} }, "1 Main Street")
+      .accountsPartial({ admin: admin.publicKey, vault: vaultPda, cityMint: cityMintPda() })
+      .signers([admin])
+      .rpc();
+    await program.methods
+      .setCityMetadataField(cityName, { uri: {} }, "https://example.com/testcity-v2.json")
+      .accountsPartial({ admin: admin.publicKey, vault: vaultPda, cityMint: cityMintPda() })
+      .signers([admin])
+      .rpc();
+
+    metadata = await getTokenMetadata(
+      provider.connection,
+      cityMintPda(),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.deepEqual(metadata.additionalMetadata, [["property_address", "1 Main Street"]]);
+    assert.equal(metadata.uri, "https://example.com/testcity-v2.json");
+    const [cityConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("city-config"), Buffer.from(cityName)],
+      program.programId
+    );
+    const cityConfig = await program.account.cityConfig.fetch(cityConfigPda);
+    assert.equal(cityConfig.metadataUri, "https://example.com/testcity-v2.json");
+  });
+
+  it("should update the city name, symbol and URI together", async () => {
+    await program.methods
+      .updateCityMetadata(
+        cityName,
+        "Test City Residences",
+        "TCR",
+        "https://example.com/testcity-appraisal-2.json"
+      )
+      .accountsPartial({ admin: admin.publicKey, vault: vaultPda, cityMint: cityMintPda() })
+      .signers([admin])
+      .rpc();
+
+    const metadata = await getTokenMetadata(
+      provider.connection,
+      cityMintPda(),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(metadata.name, "Test City Residences");
+    assert.equal(metadata.symbol, "TCR");
+    assert.equal(metadata.uri, "https://example.com/testcity-appraisal-2.json");
+    const [cityConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("city-config"), Buffer.from(cityName)],
+      program.programId
+    );
+    const cityConfig = await program.account.cityConfig.fetch(cityConfigPda);
+    assert.equal(cityConfig.metadataUri, "https://example.com/testcity-appraisal-2.json");
+  });
+
+  // Redemption is terminal, so this runs last
+  it("should let holders sell a redeemed city at its final price however old", async () => {
+    const setStatus = (status: any) =>
+      program.methods
+        .setCityStatus(cityName, status)
+        .accountsPartial({ admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    await setStatus({ delisted: {} });
+    await setStatus({ redeemed: {} });
+
+    const tokensBefore = (await getAccount(provider.connection, userCityAta())).amount;
+    await configureOracle(testMaxPriceAge, new anchor.BN(1));
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+    try {
+      await sellTokens(new anchor.BN(1_000_000), new anchor.BN(0), null);
+    } finally {
+      await configureOracle(testMaxPriceAge);
+    }
+
+    const tokensAfter = (await getAccount(provider.connection, userCityAta())).amount;
+    assert.equal((tokensBefore - tokensAfter).toString(), "1000000");
+  });
 });